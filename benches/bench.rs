@@ -38,12 +38,25 @@ fn sigs_with_distinct_keys() -> impl Iterator<Item = Item> {
     })
 }
 
-fn bench_batch_verify(c: &mut Criterion) {
-    let mut group = c.benchmark_group("Batch Verification");
+fn sigs_with_same_key() -> impl Iterator<Item = Item> {
+    let sk = SigningKey::<SpendAuth>::new(thread_rng());
+    let vk_bytes = VerificationKey::from(&sk).into();
+    std::iter::repeat_with(move || {
+        let sig = sk.sign(thread_rng(), b"Bench");
+        Item::SpendAuth { vk_bytes, sig }
+    })
+}
+
+fn bench_batch_verify_with(
+    c: &mut Criterion,
+    name: &str,
+    sigs: fn() -> Box<dyn Iterator<Item = Item>>,
+) {
+    let mut group = c.benchmark_group(name);
     for &n in [8usize, 16, 24, 32, 40, 48, 56, 64].iter() {
         group.throughput(Throughput::Elements(n as u64));
 
-        let sigs = sigs_with_distinct_keys().take(n).collect::<Vec<_>>();
+        let sigs = sigs().take(n).collect::<Vec<_>>();
 
         group.bench_with_input(
             BenchmarkId::new("Unbatched verification", n),
@@ -92,5 +105,17 @@ fn bench_batch_verify(c: &mut Criterion) {
     group.finish();
 }
 
-criterion_group!(benches, bench_batch_verify);
+fn bench_batch_verify(c: &mut Criterion) {
+    bench_batch_verify_with(c, "Batch Verification", || {
+        Box::new(sigs_with_distinct_keys())
+    });
+}
+
+fn bench_batch_verify_same_key(c: &mut Criterion) {
+    bench_batch_verify_with(c, "Batch Verification (same key)", || {
+        Box::new(sigs_with_same_key())
+    });
+}
+
+criterion_group!(benches, bench_batch_verify, bench_batch_verify_same_key);
 criterion_main!(benches);
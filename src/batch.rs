@@ -7,17 +7,142 @@
 //! caller code (which must assemble a batch of signatures across work-items),
 //! and loss of the ability to easily pinpoint failing signatures.
 //!
+#![cfg_attr(
+    feature = "alloc",
+    doc = "The [`Verifier`] requires the `alloc` feature. In `no_std` environments"
+)]
+#![cfg_attr(
+    not(feature = "alloc"),
+    doc = "The `Verifier` requires the `alloc` feature. In `no_std` environments"
+)]
+//! without an allocator, the [`ArrayVerifier`] verifies a batch of bounded size
+//! without allocating.
+//!
+#![cfg_attr(
+    feature = "alloc",
+    doc = "The [`StreamingVerifier`] verifies unbounded streams of items in chunks of
+a fixed size, keeping memory use bounded.
+
+"
+)]
+//! With the `tracing` feature, batch verification emits spans for
+//! decompression and the multiscalar multiplication, debug events for failure
+//! causes and fallbacks to single verification, and counters following the
+//! `monotonic_counter.*` field convention of `tracing-opentelemetry`:
+//! `batch_verifications`, `batch_failures`, `batch_items`,
+//! `batch_spend_auth_items` and `batch_binding_items` for each verified batch,
+//! and `batch_fallbacks` and `batch_fallback_items` for each fallback.
+//!
+//! Batches of many keys signing the same message are no cheaper to queue than
+//! any other batch. The challenge hash is `H(R || vk || msg)`, with the
+//! message last, so no hash state can be shared between signatures: each
+//! distinct `R` and key change the first BLAKE2b block, and the whole message
+//! must be hashed again for every signature. Callers with long messages should
+//! sign a digest of the message instead.
+//!
+//! Items and batches have a versioned wire encoding, so that verification can
+//! be distributed among processes. An [`Item`] is encoded in [`ITEM_LEN`]
+//! bytes: the encoding version ([`ENCODING_VERSION`]), the domain tag (`1`
+//! SpendAuth, `2` Binding), the verification key, the signature, and the
+//! challenge. A batch is encoded as the encoding version and the number of
+//! items as 4 little-endian bytes, followed by the encoding of each item.
+//!
+//! **Decoded items prove nothing unless they come from a trusted source.** The
+//! encoding carries the challenge `c` instead of the message, and the verifier
+//! cannot check that `c` is the hash of the signature, key and message. Anyone
+//! can therefore encode an item that passes verification for any key, by
+//! choosing `s` and `c` and setting `R = [s]B - [c]VK`. The wire encoding is
+//! only meant for shipping items between processes that trust each other, such
+//! as a coordinator and its local workers; items received from untrusted peers
+//! must be rebuilt from the key, signature and message instead.
 
-use std::convert::TryFrom;
+#[cfg(feature = "alloc")]
+use alloc::{
+    boxed::Box,
+    collections::btree_map::{BTreeMap, Entry},
+    vec::Vec,
+};
 
+use blake2b_simd::{Params, State};
 use decaf377::{Element, Fr};
-use rand_core::{CryptoRng, RngCore};
+use rand_core::{impls, CryptoRng, RngCore};
 
 use crate::{
-    domain::Sealed, Binding, Error, HStar, Signature, SpendAuth, VerificationKey,
-    VerificationKeyBytes,
+    domain::Sealed, possession, Binding, Error, ProofOfPossession, Signature, SpendAuth,
+    VerificationKey, VerificationKeyBytes,
 };
 
+#[cfg(feature = "cache")]
+use crate::cache::{CacheKey, VerificationCache};
+#[cfg(feature = "alloc")]
+use crate::Domain;
+
+/// Emits a `tracing` event at the debug level if the `tracing` feature is
+/// enabled, and expands to nothing otherwise.
+#[allow(unused_macros)]
+macro_rules! debug_event {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "tracing")]
+        tracing::debug!($($arg)*);
+    };
+}
+
+/// Emits the counters for a verified batch with `spend_auth` and `binding`
+/// items.
+#[cfg(feature = "tracing")]
+fn record_batch(spend_auth: usize, binding: usize, result: &Result<(), Error>) {
+    tracing::debug!(
+        monotonic_counter.batch_verifications = 1u64,
+        monotonic_counter.batch_failures = u64::from(result.is_err()),
+        monotonic_counter.batch_items = (spend_auth + binding) as u64,
+        monotonic_counter.batch_spend_auth_items = spend_auth as u64,
+        monotonic_counter.batch_binding_items = binding as u64,
+        "batch verification finished"
+    );
+}
+
+/// Emits the counters for a fallback to single verification of `items`
+/// items.
+#[cfg(all(feature = "alloc", feature = "tracing"))]
+fn record_fallback(items: usize) {
+    tracing::debug!(
+        monotonic_counter.batch_fallbacks = 1u64,
+        monotonic_counter.batch_fallback_items = items as u64,
+        "falling back to single verification"
+    );
+}
+
+#[cfg(feature = "service")]
+pub mod service;
+
+/// The version of the wire encoding of items and batches.
+pub const ENCODING_VERSION: u8 = 1;
+
+/// The length of an encoded [`Item`].
+pub const ITEM_LEN: usize = 130;
+
+/// Computes `sum([scalar_i]point_i)`, using the `decaf377` multiscalar
+/// multiplication when it is available.
+fn vartime_multiscalar_mul<'a, I, J>(scalars: I, points: J) -> Element
+where
+    I: IntoIterator<Item = &'a Fr>,
+    J: IntoIterator<Item = &'a Element>,
+{
+    #[cfg(feature = "std")]
+    {
+        Element::vartime_multiscalar_mul(scalars, points)
+    }
+    #[cfg(not(feature = "std"))]
+    {
+        scalars
+            .into_iter()
+            .zip(points)
+            .fold(Element::IDENTITY, |acc, (scalar, point)| {
+                acc + point * scalar
+            })
+    }
+}
+
 // Shim to generate a random 128bit Fr value.
 fn gen_128_bits<R: RngCore + CryptoRng>(mut rng: R) -> Fr {
     let lo = rng.next_u64() as u128;
@@ -25,7 +150,80 @@ fn gen_128_bits<R: RngCore + CryptoRng>(mut rng: R) -> Fr {
     (lo + (hi << 64)).into()
 }
 
-#[derive(Clone, Debug)]
+/// A deterministic source of batch verification coefficients.
+///
+/// The coefficients are the output of BLAKE2b-512 with the personalization
+/// `decaf377-rdsa-bv`, keyed by a hash of the entire batch (see
+/// [`BatchRng::new`]) and applied to a block counter, so an adversary cannot
+/// choose signatures after learning their coefficients.
+struct BatchRng {
+    seed: [u8; 64],
+    counter: u64,
+    block: [u8; 64],
+    offset: usize,
+}
+
+const BATCH_PERSONAL: &[u8; 16] = b"decaf377-rdsa-bv";
+
+impl BatchRng {
+    /// Derive the coefficients from the domain tag, verification key,
+    /// signature, and challenge of each of the `len` items in the batch.
+    fn new<'a>(len: usize, items: impl Iterator<Item = &'a Item>) -> Self {
+        let mut state = Params::new()
+            .hash_length(64)
+            .personal(BATCH_PERSONAL)
+            .to_state();
+        state.update(&(len as u64).to_le_bytes());
+        for item in items {
+            item.update_transcript(&mut state);
+        }
+        Self {
+            seed: *state.finalize().as_array(),
+            counter: 0,
+            block: [0u8; 64],
+            offset: 64,
+        }
+    }
+}
+
+impl RngCore for BatchRng {
+    fn next_u32(&mut self) -> u32 {
+        impls::next_u32_via_fill(self)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        impls::next_u64_via_fill(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for byte in dest {
+            if self.offset == self.block.len() {
+                self.block = *Params::new()
+                    .hash_length(64)
+                    .personal(BATCH_PERSONAL)
+                    .key(&self.seed)
+                    .to_state()
+                    .update(&self.counter.to_le_bytes())
+                    .finalize()
+                    .as_array();
+                self.counter += 1;
+                self.offset = 0;
+            }
+            *byte = self.block[self.offset];
+            self.offset += 1;
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+impl CryptoRng for BatchRng {}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "alloc", derive(Debug))]
 enum Inner {
     SpendAuth {
         vk_bytes: VerificationKeyBytes<SpendAuth>,
@@ -39,14 +237,68 @@ enum Inner {
     },
 }
 
+/// The decompressed verification key, and optionally the parsed signature, of
+/// an [`Item`] built by [`Item::from_verification_key`] or
+/// [`Item::from_parsed`].
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug)]
+struct Parsed {
+    inner: Inner,
+    vk: Element,
+    signature: Option<(Element, Fr)>,
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "alloc", derive(Debug))]
+enum Repr {
+    Encoded(Inner),
+    /// Decompressed data is boxed, so that items built from encodings do not
+    /// grow.
+    #[cfg(feature = "alloc")]
+    Parsed(Box<Parsed>),
+}
+
 /// A batch verification item.
 ///
 /// This struct exists to allow batch processing to be decoupled from the
 /// lifetime of the message. This is useful when using the batch verification API
 /// in an async context.
-#[derive(Clone, Debug)]
+#[derive(Clone)]
+#[cfg_attr(feature = "alloc", derive(Debug))]
 pub struct Item {
-    inner: Inner,
+    repr: Repr,
+}
+
+/// A signature whose `R` value has been decompressed and whose `s` value has
+/// been parsed, for building batch items with [`Item::from_parsed`].
+#[cfg(feature = "alloc")]
+#[derive(Copy, Clone)]
+pub struct ParsedSignature<D: Domain> {
+    signature: Signature<D>,
+    r: Element,
+    s: Fr,
+}
+
+#[cfg(feature = "alloc")]
+impl<D: Domain> ParsedSignature<D> {
+    /// Returns the signature that was parsed.
+    pub fn signature(&self) -> Signature<D> {
+        self.signature
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<D: Domain> TryFrom<Signature<D>> for ParsedSignature<D> {
+    type Error = Error;
+
+    fn try_from(signature: Signature<D>) -> Result<Self, Self::Error> {
+        let r = decaf377::Encoding(signature.r_bytes())
+            .vartime_decompress()
+            .map_err(|_| Error::InvalidSignature)?;
+        let s =
+            Fr::from_bytes_checked(&signature.s_bytes()).map_err(|_| Error::InvalidSignature)?;
+        Ok(Self { signature, r, s })
+    }
 }
 
 impl<'msg, M: AsRef<[u8]>>
@@ -64,13 +316,9 @@ impl<'msg, M: AsRef<[u8]>>
         ),
     ) -> Self {
         // Compute c now to avoid dependency on the msg lifetime.
-        let c = HStar::default()
-            .update(&sig.r_bytes()[..])
-            .update(&vk_bytes.bytes[..])
-            .update(msg)
-            .finalize();
+        let c = vk_bytes.challenge(msg.as_ref(), &sig);
         Self {
-            inner: Inner::SpendAuth { vk_bytes, sig, c },
+            repr: Repr::Encoded(Inner::SpendAuth { vk_bytes, sig, c }),
         }
     }
 }
@@ -82,18 +330,146 @@ impl<'msg, M: AsRef<[u8]>> From<(VerificationKeyBytes<Binding>, Signature<Bindin
         (vk_bytes, sig, msg): (VerificationKeyBytes<Binding>, Signature<Binding>, &'msg M),
     ) -> Self {
         // Compute c now to avoid dependency on the msg lifetime.
-        let c = HStar::default()
-            .update(&sig.r_bytes()[..])
-            .update(&vk_bytes.bytes[..])
-            .update(msg)
-            .finalize();
+        let c = vk_bytes.challenge(msg.as_ref(), &sig);
+        Self {
+            repr: Repr::Encoded(Inner::Binding { vk_bytes, sig, c }),
+        }
+    }
+}
+
+impl
+    From<(
+        VerificationKeyBytes<SpendAuth>,
+        ProofOfPossession<SpendAuth>,
+    )> for Item
+{
+    fn from(
+        (vk_bytes, proof): (
+            VerificationKeyBytes<SpendAuth>,
+            ProofOfPossession<SpendAuth>,
+        ),
+    ) -> Self {
+        let sig = proof.signature;
+        let c = possession::challenge(&sig.r_bytes(), &vk_bytes);
         Self {
-            inner: Inner::Binding { vk_bytes, sig, c },
+            repr: Repr::Encoded(Inner::SpendAuth { vk_bytes, sig, c }),
+        }
+    }
+}
+
+impl From<(VerificationKeyBytes<Binding>, ProofOfPossession<Binding>)> for Item {
+    fn from(
+        (vk_bytes, proof): (VerificationKeyBytes<Binding>, ProofOfPossession<Binding>),
+    ) -> Self {
+        let sig = proof.signature;
+        let c = possession::challenge(&sig.r_bytes(), &vk_bytes);
+        Self {
+            repr: Repr::Encoded(Inner::Binding { vk_bytes, sig, c }),
         }
     }
 }
 
 impl Item {
+    /// Construct an `Item` for a signature over `msg` from an already
+    /// decompressed verification key.
+    ///
+    /// Unlike items built from [`VerificationKeyBytes`], the key is then not
+    /// decompressed again by [`Item::verify_single`] or the batch verifiers,
+    /// which avoids redundant work for callers that keep decompressed keys.
+    #[cfg(feature = "alloc")]
+    pub fn from_verification_key<'msg, D: Domain, M: AsRef<[u8]>>(
+        vk: VerificationKey<D>,
+        sig: Signature<D>,
+        msg: &'msg M,
+    ) -> Self
+    where
+        Self: From<(VerificationKeyBytes<D>, Signature<D>, &'msg M)>,
+    {
+        Self::from((vk.bytes, sig, msg)).with_parsed(vk.point, None)
+    }
+
+    /// Construct an `Item` for a signature over `msg` from an already
+    /// decompressed verification key and an already parsed signature.
+    ///
+    /// As with [`Item::from_verification_key`], neither the key nor the
+    /// signature are decompressed again by [`Item::verify_single`] or the
+    /// batch verifiers.
+    #[cfg(feature = "alloc")]
+    pub fn from_parsed<'msg, D: Domain, M: AsRef<[u8]>>(
+        vk: VerificationKey<D>,
+        sig: ParsedSignature<D>,
+        msg: &'msg M,
+    ) -> Self
+    where
+        Self: From<(VerificationKeyBytes<D>, Signature<D>, &'msg M)>,
+    {
+        Self::from((vk.bytes, sig.signature, msg)).with_parsed(vk.point, Some((sig.r, sig.s)))
+    }
+
+    #[cfg(feature = "alloc")]
+    fn with_parsed(self, vk: Element, signature: Option<(Element, Fr)>) -> Self {
+        let inner = self.inner().clone();
+        Self {
+            repr: Repr::Parsed(Box::new(Parsed {
+                inner,
+                vk,
+                signature,
+            })),
+        }
+    }
+
+    fn inner(&self) -> &Inner {
+        match &self.repr {
+            Repr::Encoded(inner) => inner,
+            #[cfg(feature = "alloc")]
+            Repr::Parsed(parsed) => &parsed.inner,
+        }
+    }
+
+    /// Returns `true` if this `Item` holds a `SpendAuth` signature.
+    pub fn is_spend_auth(&self) -> bool {
+        matches!(self.inner(), Inner::SpendAuth { .. })
+    }
+
+    /// Returns `true` if this `Item` holds a `Binding` signature.
+    pub fn is_binding(&self) -> bool {
+        matches!(self.inner(), Inner::Binding { .. })
+    }
+
+    /// Returns the encoding of the verification key of this `Item`.
+    pub fn verification_key_bytes(&self) -> [u8; 32] {
+        match *self.inner() {
+            Inner::SpendAuth { vk_bytes, .. } => vk_bytes.bytes,
+            Inner::Binding { vk_bytes, .. } => vk_bytes.bytes,
+        }
+    }
+
+    /// Returns the encoding of the signature of this `Item`.
+    pub fn signature_bytes(&self) -> [u8; 64] {
+        match *self.inner() {
+            Inner::SpendAuth { sig, .. } => sig.to_bytes(),
+            Inner::Binding { sig, .. } => sig.to_bytes(),
+        }
+    }
+
+    /// Returns the wire encoding of this `Item`.
+    ///
+    /// A decompressed verification key carried by the item is not encoded,
+    /// and will be decompressed again by the receiver.
+    pub fn to_bytes(&self) -> [u8; ITEM_LEN] {
+        let (tag, c) = match *self.inner() {
+            Inner::SpendAuth { c, .. } => (SpendAuth::TAG, c),
+            Inner::Binding { c, .. } => (Binding::TAG, c),
+        };
+        let mut bytes = [0u8; ITEM_LEN];
+        bytes[0] = ENCODING_VERSION;
+        bytes[1] = tag;
+        bytes[2..34].copy_from_slice(&self.verification_key_bytes());
+        bytes[34..98].copy_from_slice(&self.signature_bytes());
+        bytes[98..130].copy_from_slice(&c.to_bytes());
+        bytes
+    }
+
     /// Perform non-batched verification of this `Item`.
     ///
     /// This is useful (in combination with `Item::clone`) for implementing fallback
@@ -103,35 +479,296 @@ impl Item {
     /// the message.
     #[allow(non_snake_case)]
     pub fn verify_single(self) -> Result<(), Error> {
-        match self.inner {
-            Inner::Binding { vk_bytes, sig, c } => VerificationKey::<Binding>::try_from(vk_bytes)
-                .and_then(|vk| vk.verify_prehashed(&sig, c)),
+        let point = self.verification_key_point()?;
+        let R = self.r_point()?;
+        let (s, c) = self.scalar_terms()?;
+        match *self.inner() {
+            Inner::Binding { vk_bytes, .. } => VerificationKey {
+                point,
+                bytes: vk_bytes,
+            }
+            .verify_parsed(R, s, c),
+            Inner::SpendAuth { vk_bytes, .. } => VerificationKey {
+                point,
+                bytes: vk_bytes,
+            }
+            .verify_parsed(R, s, c),
+        }
+    }
+
+    /// Decompress the signature and verification key of this `Item`, returning
+    /// the terms `(s, R, VK, c)` it contributes to the batch verification
+    /// equation.
+    #[allow(non_snake_case)]
+    fn terms(&self) -> Result<(Fr, Element, Element, Fr), Error> {
+        let (s, c) = self.scalar_terms()?;
+        Ok((s, self.r_point()?, self.verification_key_point()?, c))
+    }
+
+    /// Returns the signature parsed by the caller, if any.
+    fn parsed_signature(&self) -> Option<(Element, Fr)> {
+        match &self.repr {
+            Repr::Encoded(_) => None,
+            #[cfg(feature = "alloc")]
+            Repr::Parsed(parsed) => parsed.signature,
+        }
+    }
+
+    /// Decompress the signature's `R` value, unless the signature was already
+    /// parsed.
+    #[allow(non_snake_case)]
+    fn r_point(&self) -> Result<Element, Error> {
+        if let Some((R, _)) = self.parsed_signature() {
+            return Ok(R);
+        }
+        let r_bytes = match *self.inner() {
+            Inner::SpendAuth { sig, .. } => sig.r_bytes(),
+            Inner::Binding { sig, .. } => sig.r_bytes(),
+        };
+        decaf377::Encoding(r_bytes)
+            .vartime_decompress()
+            .map_err(|_| Error::InvalidSignature)
+    }
+
+    /// Parse the signature's `s` value, unless it was already parsed,
+    /// returning the scalar terms `(s, c)` this `Item` contributes to the batch
+    /// verification equation.
+    fn scalar_terms(&self) -> Result<(Fr, Fr), Error> {
+        let (s_bytes, c) = match *self.inner() {
+            Inner::SpendAuth { sig, c, .. } => (sig.s_bytes(), c),
+            Inner::Binding { sig, c, .. } => (sig.s_bytes(), c),
+        };
+        if let Some((_, s)) = self.parsed_signature() {
+            return Ok((s, c));
+        }
+        let s = Fr::from_bytes_checked(&s_bytes).map_err(|_| Error::InvalidSignature)?;
+        Ok((s, c))
+    }
+
+    /// Decompress the verification key of this `Item`, unless it was built
+    /// from an already decompressed key.
+    fn verification_key_point(&self) -> Result<Element, Error> {
+        #[cfg(feature = "alloc")]
+        if let Repr::Parsed(parsed) = &self.repr {
+            return Ok(parsed.vk);
+        }
+        Ok(match *self.inner() {
+            Inner::SpendAuth { vk_bytes, .. } => {
+                VerificationKey::<SpendAuth>::try_from(vk_bytes.bytes)?.point
+            }
+            Inner::Binding { vk_bytes, .. } => {
+                VerificationKey::<Binding>::try_from(vk_bytes.bytes)?.point
+            }
+        })
+    }
+
+    /// Returns the domain tag and encoding of the verification key of this
+    /// `Item`, which identify the key across domains.
+    #[cfg(feature = "alloc")]
+    fn key_id(&self) -> (u8, [u8; 32]) {
+        match *self.inner() {
+            Inner::SpendAuth { vk_bytes, .. } => (SpendAuth::TAG, vk_bytes.bytes),
+            Inner::Binding { vk_bytes, .. } => (Binding::TAG, vk_bytes.bytes),
+        }
+    }
+
+    /// Add the data of this `Item` to the hash used to derive deterministic
+    /// batch verification coefficients.
+    fn update_transcript(&self, state: &mut State) {
+        let (tag, vk_bytes, sig_bytes, c) = match self.inner() {
             Inner::SpendAuth { vk_bytes, sig, c } => {
-                VerificationKey::<SpendAuth>::try_from(vk_bytes)
-                    .and_then(|vk| vk.verify_prehashed(&sig, c))
+                (SpendAuth::TAG, vk_bytes.bytes, sig.to_bytes(), c)
             }
+            Inner::Binding { vk_bytes, sig, c } => {
+                (Binding::TAG, vk_bytes.bytes, sig.to_bytes(), c)
+            }
+        };
+        state
+            .update(&[tag])
+            .update(&vk_bytes)
+            .update(&sig_bytes)
+            .update(&c.to_bytes());
+    }
+
+    /// Perform non-batched verification of this `Item`, consulting and
+    /// populating `cache`.
+    #[cfg(feature = "cache")]
+    pub fn verify_single_cached(self, cache: &VerificationCache) -> Result<(), Error> {
+        let key = self.cache_key();
+        if cache.contains(&key) {
+            return Ok(());
+        }
+        self.verify_single()?;
+        cache.insert(key);
+        Ok(())
+    }
+
+    #[cfg(feature = "cache")]
+    fn cache_key(&self) -> CacheKey {
+        match self.inner() {
+            Inner::SpendAuth { vk_bytes, sig, c } => CacheKey::new(vk_bytes, sig, c),
+            Inner::Binding { vk_bytes, sig, c } => CacheKey::new(vk_bytes, sig, c),
         }
     }
 }
 
-#[derive(Default)]
+/// Decodes an `Item` from its wire encoding.
+///
+/// The decoded challenge is trusted as is, so a decoded item proves nothing
+/// unless the encoding comes from a trusted source; see the [module
+/// documentation](self).
+impl TryFrom<&[u8]> for Item {
+    type Error = Error;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        if bytes.len() != ITEM_LEN {
+            return Err(Error::WrongSliceLength {
+                expected: ITEM_LEN,
+                found: bytes.len(),
+            });
+        }
+        if bytes[0] != ENCODING_VERSION {
+            return Err(Error::MalformedBatchItem);
+        }
+        let vk_bytes: [u8; 32] = bytes[2..34].try_into().expect("32 bytes");
+        let sig: [u8; 64] = bytes[34..98].try_into().expect("64 bytes");
+        let c = Fr::from_bytes_checked(bytes[98..130].try_into().expect("32 bytes"))
+            .map_err(|_| Error::MalformedBatchItem)?;
+
+        let inner = match bytes[1] {
+            SpendAuth::TAG => Inner::SpendAuth {
+                vk_bytes: vk_bytes.into(),
+                sig: sig.into(),
+                c,
+            },
+            Binding::TAG => Inner::Binding {
+                vk_bytes: vk_bytes.into(),
+                sig: sig.into(),
+                c,
+            },
+            _ => return Err(Error::MalformedBatchItem),
+        };
+        Ok(Self {
+            repr: Repr::Encoded(inner),
+        })
+    }
+}
+
+impl From<Item> for [u8; ITEM_LEN] {
+    fn from(item: Item) -> [u8; ITEM_LEN] {
+        item.to_bytes()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl TryFrom<Vec<u8>> for Item {
+    type Error = Error;
+
+    fn try_from(bytes: Vec<u8>) -> Result<Self, Self::Error> {
+        bytes.as_slice().try_into()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<Item> for Vec<u8> {
+    fn from(item: Item) -> Vec<u8> {
+        item.to_bytes().into()
+    }
+}
+
+#[derive(Debug, Default)]
+#[cfg(feature = "alloc")]
 /// A batch verification context.
 pub struct Verifier {
     /// Signature data queued for verification.
     signatures: Vec<Item>,
 }
 
+#[cfg(feature = "alloc")]
 impl Verifier {
     /// Construct a new batch verifier.
     pub fn new() -> Verifier {
         Verifier::default()
     }
 
+    /// Construct a new batch verifier with room for `capacity` items.
+    pub fn with_capacity(capacity: usize) -> Verifier {
+        Verifier {
+            signatures: Vec::with_capacity(capacity),
+        }
+    }
+
     /// Queue an Item for verification.
     pub fn queue<I: Into<Item>>(&mut self, item: I) {
         self.signatures.push(item.into());
     }
 
+    /// Returns the number of items queued for verification.
+    pub fn len(&self) -> usize {
+        self.signatures.len()
+    }
+
+    /// Returns `true` if no items are queued for verification.
+    pub fn is_empty(&self) -> bool {
+        self.signatures.is_empty()
+    }
+
+    /// Returns the number of items the verifier can hold without
+    /// reallocating.
+    pub fn capacity(&self) -> usize {
+        self.signatures.capacity()
+    }
+
+    /// Reserve room for at least `additional` more items.
+    pub fn reserve(&mut self, additional: usize) {
+        self.signatures.reserve(additional);
+    }
+
+    /// Returns the items queued for verification.
+    pub fn items(&self) -> &[Item] {
+        &self.signatures
+    }
+
+    /// Remove all queued items, keeping the allocated capacity.
+    pub fn clear(&mut self) {
+        self.signatures.clear();
+    }
+
+    /// Move all items queued in `other` into this verifier, so that they are
+    /// verified in the same batch.
+    pub fn merge(&mut self, mut other: Verifier) {
+        self.signatures.append(&mut other.signatures);
+    }
+
+    /// Split this verifier into at most `parts` verifiers of nearly equal
+    /// size, for example to distribute a batch among workers.
+    ///
+    /// The whole batch is valid if and only if each part is valid. At least
+    /// one verifier is returned, even if `parts` is zero or the batch is
+    /// empty.
+    pub fn split(mut self, parts: usize) -> Vec<Verifier> {
+        let parts = parts.clamp(1, self.signatures.len().max(1));
+        let mut verifiers = Vec::with_capacity(parts);
+        for i in (0..parts).rev() {
+            let len = self.signatures.len() / (i + 1);
+            let rest = self.signatures.split_off(self.signatures.len() - len);
+            verifiers.push(Verifier { signatures: rest });
+        }
+        verifiers.reverse();
+        verifiers
+    }
+
+    /// Returns the wire encoding of the queued items.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(5 + ITEM_LEN * self.signatures.len());
+        bytes.push(ENCODING_VERSION);
+        bytes.extend_from_slice(&(self.signatures.len() as u32).to_le_bytes());
+        for item in &self.signatures {
+            bytes.extend_from_slice(&item.to_bytes());
+        }
+        bytes
+    }
+
     /// Perform batch verification, returning `Ok(())` if all signatures were
     /// valid and `Err` otherwise.
     ///
@@ -158,6 +795,10 @@ impl Verifier {
     /// - h_G is the cofactor of the group;
     /// - P_G is the generator of the subgroup;
     ///
+    /// Items that share a verification key contribute a single term
+    /// `[sum(z_i * c_i)]VK` to the multiscalar multiplication, and each
+    /// distinct key is decompressed only once.
+    ///
     /// Since `decaf377-rdsa` uses a different generator for each signature
     /// domain, we have a separate scalar accumulator for each domain, but we
     /// can still amortize computation nicely in one multiscalar multiplication:
@@ -172,10 +813,48 @@ impl Verifier {
     /// notation in the [Zcash protocol specification §B.1][ps].
     ///
     /// [ps]: https://zips.z.cash/protocol/protocol.pdf#reddsabatchverify
+    pub fn verify<R: RngCore + CryptoRng>(self, rng: R) -> Result<(), Error> {
+        self.verify_items(rng)
+    }
+
+    /// Perform batch verification as in [`Verifier::verify`], then clear the
+    /// queued items, keeping the allocated capacity for reuse.
+    ///
+    /// The items are cleared whether or not verification succeeds.
+    pub fn verify_and_clear<R: RngCore + CryptoRng>(&mut self, rng: R) -> Result<(), Error> {
+        let result = self.verify_items(rng);
+        self.signatures.clear();
+        result
+    }
+
+    fn verify_items<R: RngCore + CryptoRng>(&self, rng: R) -> Result<(), Error> {
+        #[cfg(feature = "tracing")]
+        let spend_auth = self.signatures.iter().filter(|i| i.is_spend_auth()).count();
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!(
+            "batch_verify",
+            items = self.signatures.len(),
+            spend_auth,
+            binding = self.signatures.len() - spend_auth,
+        )
+        .entered();
+
+        let result = self.verify_items_inner(rng);
+        if result.is_ok() {
+            debug_event!("batch verification succeeded");
+        }
+        #[cfg(feature = "tracing")]
+        record_batch(spend_auth, self.signatures.len() - spend_auth, &result);
+        result
+    }
+
     #[allow(non_snake_case)]
-    pub fn verify<R: RngCore + CryptoRng>(self, mut rng: R) -> Result<(), Error> {
+    fn verify_items_inner<R: RngCore + CryptoRng>(&self, mut rng: R) -> Result<(), Error> {
         let n = self.signatures.len();
 
+        #[cfg(feature = "tracing")]
+        let decompress_span = tracing::debug_span!("decompress").entered();
+
         let mut VK_coeffs = Vec::with_capacity(n);
         let mut VKs = Vec::with_capacity(n);
         let mut R_coeffs = Vec::with_capacity(self.signatures.len());
@@ -183,30 +862,29 @@ impl Verifier {
         let mut P_spendauth_coeff = Fr::ZERO;
         let mut P_binding_coeff = Fr::ZERO;
 
-        for item in self.signatures.iter() {
-            let (s_bytes, r_bytes, c) = match item.inner {
-                Inner::SpendAuth { sig, c, .. } => (sig.s_bytes(), sig.r_bytes(), c),
-                Inner::Binding { sig, c, .. } => (sig.s_bytes(), sig.r_bytes(), c),
-            };
-
-            let s = Fr::from_bytes_checked(&s_bytes).map_err(|_| Error::InvalidSignature)?;
-            let R = decaf377::Encoding(r_bytes)
-                .vartime_decompress()
-                .map_err(|_| Error::InvalidSignature)?;
+        // Maps each distinct verification key to its index in `VKs`.
+        let mut VK_indices = BTreeMap::new();
 
-            let VK = match item.inner {
-                Inner::SpendAuth { vk_bytes, .. } => {
-                    VerificationKey::<SpendAuth>::try_from(vk_bytes.bytes)?.point
+        for item in self.signatures.iter() {
+            let R = match item.r_point() {
+                Ok(R) => R,
+                Err(_) => {
+                    debug_event!(cause = "malformed R value", "batch verification failed");
+                    return Err(Error::InvalidSignature);
                 }
-                Inner::Binding { vk_bytes, .. } => {
-                    VerificationKey::<Binding>::try_from(vk_bytes.bytes)?.point
+            };
+            let (s, c) = match item.scalar_terms() {
+                Ok(terms) => terms,
+                Err(error) => {
+                    debug_event!(cause = "malformed s value", "batch verification failed");
+                    return Err(error);
                 }
             };
 
             let z = gen_128_bits(&mut rng);
 
             let P_coeff = z * s;
-            match item.inner {
+            match item.inner() {
                 Inner::SpendAuth { .. } => {
                     P_spendauth_coeff -= P_coeff;
                 }
@@ -218,11 +896,31 @@ impl Verifier {
             R_coeffs.push(z);
             Rs.push(R);
 
-            VK_coeffs.push(z * c);
-            VKs.push(VK);
+            match VK_indices.entry(item.key_id()) {
+                Entry::Occupied(entry) => {
+                    VK_coeffs[*entry.get()] += z * c;
+                }
+                Entry::Vacant(entry) => {
+                    match item.verification_key_point() {
+                        Ok(VK) => VKs.push(VK),
+                        Err(error) => {
+                            debug_event!(
+                                cause = "malformed verification key",
+                                "batch verification failed"
+                            );
+                            return Err(error);
+                        }
+                    }
+                    VK_coeffs.push(z * c);
+                    entry.insert(VKs.len() - 1);
+                }
+            }
         }
 
-        use std::iter::once;
+        #[cfg(feature = "tracing")]
+        drop(decompress_span);
+
+        use core::iter::once;
 
         let scalars = once(&P_spendauth_coeff)
             .chain(once(&P_binding_coeff))
@@ -232,12 +930,375 @@ impl Verifier {
         let basepoints = [SpendAuth::basepoint(), Binding::basepoint()];
         let points = basepoints.iter().chain(VKs.iter()).chain(Rs.iter());
 
-        let check = Element::vartime_multiscalar_mul(scalars, points);
+        let check = {
+            #[cfg(feature = "tracing")]
+            let _span = tracing::debug_span!(
+                "multiscalar_mul",
+                terms = 2 + VKs.len() + Rs.len(),
+                distinct_keys = VKs.len(),
+            )
+            .entered();
+            vartime_multiscalar_mul(scalars, points)
+        };
+
+        if check.is_identity() {
+            Ok(())
+        } else {
+            debug_event!(
+                cause = "batch equation not satisfied",
+                "batch verification failed"
+            );
+            Err(Error::InvalidSignature)
+        }
+    }
+
+    /// Perform batch verification as in [`Verifier::verify`], but deriving the
+    /// coefficients `z_i` by hashing the entire batch rather than from an RNG.
+    ///
+    /// The result is reproducible, and verification needs no RNG. The batch
+    /// is hashed with BLAKE2b-512 with the personalization
+    /// `decaf377-rdsa-bv`, over the number of items followed by the domain
+    /// tag, verification key, signature, and challenge of each item, so every
+    /// coefficient depends on every signature in the batch.
+    pub fn verify_deterministic(self) -> Result<(), Error> {
+        let rng = BatchRng::new(self.signatures.len(), self.signatures.iter());
+        self.verify(rng)
+    }
+
+    /// Perform batch verification as in [`Verifier::verify`], consulting and
+    /// populating `cache`.
+    ///
+    /// Items already recorded in `cache` are skipped, and the remaining items
+    /// are recorded only if the whole batch is valid.
+    #[cfg(feature = "cache")]
+    pub fn verify_cached<R: RngCore + CryptoRng>(
+        self,
+        rng: R,
+        cache: &VerificationCache,
+    ) -> Result<(), Error> {
+        let (keys, signatures): (Vec<_>, Vec<_>) = self
+            .signatures
+            .into_iter()
+            .map(|item| (item.cache_key(), item))
+            .filter(|(key, _)| !cache.contains(key))
+            .unzip();
+        debug_event!(uncached = signatures.len(), "skipping cached batch items");
+
+        Verifier { signatures }.verify(rng)?;
+
+        for key in keys {
+            cache.insert(key);
+        }
+        Ok(())
+    }
+}
+
+/// Decodes a batch from its wire encoding.
+///
+/// As with [`Item`], the decoded challenges are trusted as is, so a decoded
+/// batch proves nothing unless the encoding comes from a trusted source; see
+/// the [module documentation](self).
+#[cfg(feature = "alloc")]
+impl TryFrom<&[u8]> for Verifier {
+    type Error = Error;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        if bytes.len() < 5 || bytes[0] != ENCODING_VERSION {
+            return Err(Error::MalformedBatchItem);
+        }
+        let n = u32::from_le_bytes(bytes[1..5].try_into().expect("4 bytes")) as usize;
+        let body = &bytes[5..];
+        if n.checked_mul(ITEM_LEN) != Some(body.len()) {
+            return Err(Error::MalformedBatchItem);
+        }
+        body.chunks_exact(ITEM_LEN).map(Item::try_from).collect()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<I: Into<Item>> Extend<I> for Verifier {
+    fn extend<T: IntoIterator<Item = I>>(&mut self, iter: T) {
+        self.signatures.extend(iter.into_iter().map(Into::into));
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<I: Into<Item>> FromIterator<I> for Verifier {
+    fn from_iter<T: IntoIterator<Item = I>>(iter: T) -> Self {
+        Verifier {
+            signatures: iter.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// A batch verification context for unbounded streams of items, which are
+/// verified in chunks of a fixed size.
+///
+/// At most one chunk of items is held at a time, so memory use is bounded
+/// regardless of the length of the stream. Items can be queued one at a time
+/// with [`StreamingVerifier::queue`], or taken from any iterator, such as a
+/// channel receiver, with [`StreamingVerifier::verify_iter`].
+///
+/// When a chunk fails verification, the error is
+/// [`Error::ChunkVerificationFailed`], reporting the index of the chunk in the
+/// stream. If [`StreamingVerifier::locate_failures`] is enabled, the items of
+/// a failing chunk are then verified one by one to also report the index of
+/// the first invalid item in the stream.
+#[cfg(feature = "alloc")]
+#[derive(Debug)]
+pub struct StreamingVerifier {
+    chunk: Verifier,
+    chunk_size: usize,
+    chunks_verified: usize,
+    locate_failures: bool,
+}
+
+#[cfg(feature = "alloc")]
+impl StreamingVerifier {
+    /// Construct a new streaming verifier that verifies chunks of
+    /// `chunk_size` items.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunk_size` is zero.
+    pub fn new(chunk_size: usize) -> Self {
+        assert!(chunk_size > 0, "chunk size must be positive");
+        Self {
+            chunk: Verifier::with_capacity(chunk_size),
+            chunk_size,
+            chunks_verified: 0,
+            locate_failures: false,
+        }
+    }
+
+    /// Set whether to locate the first invalid item when a chunk fails
+    /// verification, at the cost of verifying the items of that chunk
+    /// individually.
+    pub fn locate_failures(mut self, locate_failures: bool) -> Self {
+        self.locate_failures = locate_failures;
+        self
+    }
+
+    /// Returns the number of items in each chunk.
+    pub fn chunk_size(&self) -> usize {
+        self.chunk_size
+    }
+
+    /// Returns the number of chunks verified so far, including failed ones.
+    pub fn chunks_verified(&self) -> usize {
+        self.chunks_verified
+    }
+
+    /// Queue an Item for verification, verifying the current chunk if it is
+    /// full.
+    ///
+    /// Returns an error if the chunk fails verification. The failed chunk is
+    /// discarded, and the verifier can continue with the next one.
+    pub fn queue<R: RngCore + CryptoRng, I: Into<Item>>(
+        &mut self,
+        rng: R,
+        item: I,
+    ) -> Result<(), Error> {
+        self.chunk.queue(item);
+        if self.chunk.len() == self.chunk_size {
+            self.verify_chunk(rng)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Verify the remaining, partial chunk of items.
+    pub fn finish<R: RngCore + CryptoRng>(mut self, rng: R) -> Result<(), Error> {
+        if self.chunk.is_empty() {
+            Ok(())
+        } else {
+            self.verify_chunk(rng)
+        }
+    }
+
+    /// Verify all of `items` in chunks, stopping at the first chunk that
+    /// fails verification.
+    pub fn verify_iter<R, I, T>(mut self, mut rng: R, items: T) -> Result<(), Error>
+    where
+        R: RngCore + CryptoRng,
+        I: Into<Item>,
+        T: IntoIterator<Item = I>,
+    {
+        for item in items {
+            self.queue(&mut rng, item)?;
+        }
+        self.finish(rng)
+    }
+
+    fn verify_chunk<R: RngCore + CryptoRng>(&mut self, rng: R) -> Result<(), Error> {
+        let chunk = self.chunks_verified;
+        self.chunks_verified += 1;
+
+        let result = self.chunk.verify_items(rng).map_err(|_| {
+            let item = if self.locate_failures {
+                debug_event!(
+                    chunk,
+                    items = self.chunk.len(),
+                    "falling back to single verification to locate invalid item"
+                );
+                #[cfg(feature = "tracing")]
+                record_fallback(self.chunk.len());
+                self.chunk
+                    .items()
+                    .iter()
+                    .position(|item| item.clone().verify_single().is_err())
+                    .map(|i| chunk * self.chunk_size + i)
+            } else {
+                None
+            };
+            Error::ChunkVerificationFailed { chunk, item }
+        });
+        self.chunk.clear();
+        result
+    }
+}
+
+/// A fixed-capacity batch verification context, holding at most `N` items.
+///
+/// This does not allocate, so it can be used in `no_std` environments without
+/// an allocator. Since there is no room to
+/// store the terms of a multiscalar multiplication, each item's terms are
+/// accumulated into the check as the batch is verified.
+pub struct ArrayVerifier<const N: usize> {
+    items: [Option<Item>; N],
+    len: usize,
+}
+
+impl<const N: usize> Default for ArrayVerifier<N> {
+    fn default() -> Self {
+        Self {
+            items: core::array::from_fn(|_| None),
+            len: 0,
+        }
+    }
+}
+
+impl<const N: usize> ArrayVerifier<N> {
+    /// Construct a new, empty batch verifier.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of items queued for verification.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if no items are queued for verification.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns `true` if no more items can be queued for verification.
+    pub fn is_full(&self) -> bool {
+        self.len == N
+    }
+
+    /// Queue an Item for verification, returning it back if the batch is full.
+    // Without an allocator, there is nowhere else to put the rejected item.
+    #[allow(clippy::result_large_err)]
+    pub fn queue<I: Into<Item>>(&mut self, item: I) -> Result<(), Item> {
+        let item = item.into();
+        if self.is_full() {
+            return Err(item);
+        }
+        self.items[self.len] = Some(item);
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Perform batch verification, returning `Ok(())` if all signatures were
+    /// valid and `Err` otherwise.
+    #[cfg_attr(
+        feature = "alloc",
+        doc = "\nThis checks the same equation as [`Verifier::verify`]."
+    )]
+    pub fn verify<R: RngCore + CryptoRng>(self, rng: R) -> Result<(), Error> {
+        #[cfg(feature = "tracing")]
+        let spend_auth = self
+            .items
+            .iter()
+            .flatten()
+            .filter(|i| i.is_spend_auth())
+            .count();
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!(
+            "batch_verify",
+            items = self.len,
+            spend_auth,
+            binding = self.len - spend_auth,
+        )
+        .entered();
+
+        let result = self.verify_inner(rng);
+        if result.is_ok() {
+            debug_event!("batch verification succeeded");
+        }
+        #[cfg(feature = "tracing")]
+        record_batch(spend_auth, self.len - spend_auth, &result);
+        result
+    }
+
+    #[allow(non_snake_case)]
+    fn verify_inner<R: RngCore + CryptoRng>(&self, mut rng: R) -> Result<(), Error> {
+        let mut check = Element::IDENTITY;
+        let mut P_spendauth_coeff = Fr::ZERO;
+        let mut P_binding_coeff = Fr::ZERO;
+
+        for item in self.items.iter().flatten() {
+            let (s, R, VK, c) = match item.terms() {
+                Ok(terms) => terms,
+                Err(error) => {
+                    debug_event!(cause = "malformed item", "batch verification failed");
+                    return Err(error);
+                }
+            };
+
+            let z = gen_128_bits(&mut rng);
+
+            let P_coeff = z * s;
+            match item.inner() {
+                Inner::SpendAuth { .. } => {
+                    P_spendauth_coeff -= P_coeff;
+                }
+                Inner::Binding { .. } => {
+                    P_binding_coeff -= P_coeff;
+                }
+            };
+
+            check += vartime_multiscalar_mul([z, z * c].iter(), [R, VK].iter());
+        }
+
+        let scalars = [P_spendauth_coeff, P_binding_coeff];
+        let basepoints = [SpendAuth::basepoint(), Binding::basepoint()];
+        check += vartime_multiscalar_mul(scalars.iter(), basepoints.iter());
 
         if check.is_identity() {
             Ok(())
         } else {
+            debug_event!(
+                cause = "batch equation not satisfied",
+                "batch verification failed"
+            );
             Err(Error::InvalidSignature)
         }
     }
+
+    /// Perform batch verification, deriving the coefficients by hashing the
+    /// entire batch rather than from an RNG.
+    ///
+    /// The result is reproducible, and verification needs no RNG. The batch
+    /// is hashed with BLAKE2b-512 with the personalization
+    /// `decaf377-rdsa-bv`, over the number of items followed by the domain
+    /// tag, verification key, signature, and challenge of each item, so every
+    /// coefficient depends on every signature in the batch.
+    pub fn verify_deterministic(self) -> Result<(), Error> {
+        let rng = BatchRng::new(self.len, self.items.iter().flatten());
+        self.verify(rng)
+    }
 }
@@ -0,0 +1,211 @@
+//! An asynchronous batch verification service.
+//!
+//! The [`Service`] accepts individual [`Item`]s, accumulates them into a
+//! [`Verifier`], and verifies the batch once it reaches a maximum size or once
+//! its oldest item has waited for a maximum latency. Each caller receives the
+//! result for its own item: if the batch fails, every item in it is checked
+//! with [`Item::verify_single`] so that only the invalid items are rejected.
+//!
+//! This is modeled on Zebra's `tower-batch`, and implements
+//! [`tower_service::Service`] so it can be composed with other `tower`
+//! middleware. The service runs on the `tokio` runtime it was created in, and
+//! verifies batches on its blocking thread pool.
+//!
+//! As in `tower-batch`, requests are queued in a bounded channel, so callers
+//! experience backpressure when the service falls behind:
+//! [`Service::verify`] waits for room in the queue, and the service only
+//! reports readiness through [`tower_service::Service::poll_ready`] once it
+//! has reserved a slot for the next request. The worker also stops accepting
+//! requests while as many batches as there are available CPUs are being
+//! verified, so the memory held by the service stays bounded.
+
+use core::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use std::{sync::Arc, thread, time::Duration};
+
+use rand_core::OsRng;
+use tokio::{
+    sync::{mpsc, oneshot, Semaphore},
+    time::{sleep_until, Instant},
+};
+use tokio_util::sync::PollSender;
+
+use super::{Item, Verifier};
+use crate::Error;
+
+type Request = (Item, oneshot::Sender<Result<(), Error>>);
+
+/// A handle to a batch verification service.
+///
+/// Handles are cheap to clone, and all clones submit items to the same
+/// batches. The background worker shuts down once every handle is dropped,
+/// after verifying any items it has already accepted.
+#[derive(Clone, Debug)]
+pub struct Service {
+    tx: mpsc::Sender<Request>,
+    poll_tx: PollSender<Request>,
+}
+
+impl Service {
+    /// Start a batch verification service on the current `tokio` runtime.
+    ///
+    /// Batches are verified once they contain `max_items` items, or once the
+    /// first item in the batch has waited for `max_latency`, whichever comes
+    /// first.
+    ///
+    /// At most `max_items` requests can be queued awaiting the worker; see
+    /// [`Service::with_bound`] to choose a different bound.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside of a `tokio` runtime, or if `max_items` is zero.
+    pub fn new(max_items: usize, max_latency: Duration) -> Self {
+        Self::with_bound(max_items, max_latency, max_items)
+    }
+
+    /// Start a batch verification service as in [`Service::new`], allowing at
+    /// most `bound` requests to be queued awaiting the worker.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside of a `tokio` runtime, or if `max_items` or
+    /// `bound` is zero.
+    pub fn with_bound(max_items: usize, max_latency: Duration, bound: usize) -> Self {
+        assert!(max_items > 0, "batches must hold at least one item");
+        let (tx, rx) = mpsc::channel(bound);
+        tokio::spawn(run(rx, max_items, max_latency));
+        Self {
+            poll_tx: PollSender::new(tx.clone()),
+            tx,
+        }
+    }
+
+    /// Submit `item` for verification, waiting for room in the queue, and
+    /// return the result for that item.
+    pub async fn verify<I: Into<Item>>(&self, item: I) -> Result<(), Error> {
+        let (tx, rx) = oneshot::channel();
+        match self.tx.send((item.into(), tx)).await {
+            Ok(()) => ResponseFuture { rx: Some(rx) }.await,
+            Err(_) => Err(Error::ServiceClosed),
+        }
+    }
+}
+
+impl tower_service::Service<Item> for Service {
+    type Response = ();
+    type Error = Error;
+    type Future = ResponseFuture;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        self.poll_tx
+            .poll_reserve(cx)
+            .map_err(|_| Error::ServiceClosed)
+    }
+
+    fn call(&mut self, item: Item) -> ResponseFuture {
+        let (tx, rx) = oneshot::channel();
+        match self.poll_tx.send_item((item, tx)) {
+            Ok(()) => ResponseFuture { rx: Some(rx) },
+            Err(_) => ResponseFuture { rx: None },
+        }
+    }
+}
+
+/// The result of verifying a single [`Item`] with a [`Service`].
+#[derive(Debug)]
+pub struct ResponseFuture {
+    rx: Option<oneshot::Receiver<Result<(), Error>>>,
+}
+
+impl Future for ResponseFuture {
+    type Output = Result<(), Error>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match self.rx.as_mut() {
+            Some(rx) => Pin::new(rx)
+                .poll(cx)
+                .map(|result| result.unwrap_or(Err(Error::ServiceClosed))),
+            None => Poll::Ready(Err(Error::ServiceClosed)),
+        }
+    }
+}
+
+async fn run(mut rx: mpsc::Receiver<Request>, max_items: usize, max_latency: Duration) {
+    let mut pending = Vec::with_capacity(max_items);
+    let mut deadline = Instant::now();
+    let in_flight = Arc::new(Semaphore::new(
+        thread::available_parallelism().map_or(1, |n| n.get()),
+    ));
+
+    loop {
+        let request = if pending.is_empty() {
+            rx.recv().await
+        } else {
+            tokio::select! {
+                request = rx.recv() => request,
+                _ = sleep_until(deadline) => {
+                    flush(&mut pending, max_items, &in_flight).await;
+                    continue;
+                }
+            }
+        };
+
+        match request {
+            Some(request) => {
+                if pending.is_empty() {
+                    deadline = Instant::now() + max_latency;
+                }
+                pending.push(request);
+                if pending.len() >= max_items {
+                    flush(&mut pending, max_items, &in_flight).await;
+                }
+            }
+            None => {
+                flush(&mut pending, max_items, &in_flight).await;
+                return;
+            }
+        }
+    }
+}
+
+/// Verify the `pending` requests on the blocking thread pool, once fewer than
+/// the maximum number of batches are `in_flight`.
+async fn flush(pending: &mut Vec<Request>, max_items: usize, in_flight: &Arc<Semaphore>) {
+    if pending.is_empty() {
+        return;
+    }
+    let permit = in_flight
+        .clone()
+        .acquire_owned()
+        .await
+        .expect("semaphore is never closed");
+    let batch = core::mem::replace(pending, Vec::with_capacity(max_items));
+
+    tokio::task::spawn_blocking(move || {
+        let _permit = permit;
+        let mut verifier = Verifier::new();
+        for (item, _) in batch.iter() {
+            verifier.queue(item.clone());
+        }
+
+        if verifier.verify(OsRng).is_ok() {
+            for (_, tx) in batch {
+                let _ = tx.send(Ok(()));
+            }
+        } else {
+            // Fall back to single verification to find the invalid items.
+            debug_event!(
+                items = batch.len(),
+                "falling back to single verification to find invalid items"
+            );
+            #[cfg(feature = "tracing")]
+            super::record_fallback(batch.len());
+            for (item, tx) in batch {
+                let _ = tx.send(item.verify_single());
+            }
+        }
+    });
+}
@@ -0,0 +1,99 @@
+//! Caching of signature verification results.
+//!
+//! Signatures are often verified more than once, for instance when a
+//! transaction enters the mempool and again when it is included in a block. A
+//! [`VerificationCache`] remembers successful verifications, so that repeated
+//! checks can be skipped.
+//!
+//! Cache hits never change which signatures are accepted:
+//!
+//! - only successful verifications are recorded, so a cache hit can only
+//!   accept a signature that has already been accepted;
+//! - entries are keyed by the domain, the verification key bytes, the
+//!   signature bytes and the challenge (which binds the message), so a hit
+//!   occurs only for exactly the same verification;
+//! - keys are stored in full rather than as a digest, so distinct
+//!   verifications cannot collide.
+
+use std::{num::NonZeroUsize, sync::Mutex};
+
+use decaf377::Fr;
+use lru::LruCache;
+
+use crate::{Domain, Signature, VerificationKeyBytes};
+
+/// Identifies a single verification: a signature by a key on a challenge.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub(crate) struct CacheKey {
+    domain: u8,
+    vk_bytes: [u8; 32],
+    sig_bytes: [u8; 64],
+    c_bytes: [u8; 32],
+}
+
+impl CacheKey {
+    pub(crate) fn new<D: Domain>(
+        vk_bytes: &VerificationKeyBytes<D>,
+        sig: &Signature<D>,
+        c: &Fr,
+    ) -> Self {
+        Self {
+            domain: D::TAG,
+            vk_bytes: vk_bytes.bytes,
+            sig_bytes: sig.to_bytes(),
+            c_bytes: c.to_bytes(),
+        }
+    }
+}
+
+/// A bounded, thread-safe cache of successful signature verifications.
+///
+/// When the cache is full, the least recently used entry is evicted.
+#[derive(Debug)]
+pub struct VerificationCache {
+    entries: Mutex<LruCache<CacheKey, ()>>,
+}
+
+impl VerificationCache {
+    /// Construct a cache holding at most `capacity` verifications.
+    pub fn new(capacity: NonZeroUsize) -> Self {
+        Self {
+            entries: Mutex::new(LruCache::new(capacity)),
+        }
+    }
+
+    /// Returns the number of verifications in the cache.
+    pub fn len(&self) -> usize {
+        self.entries.lock().expect("cache lock poisoned").len()
+    }
+
+    /// Returns `true` if the cache holds no verifications.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the maximum number of verifications the cache can hold.
+    pub fn capacity(&self) -> NonZeroUsize {
+        self.entries.lock().expect("cache lock poisoned").cap()
+    }
+
+    /// Remove all verifications from the cache.
+    pub fn clear(&self) {
+        self.entries.lock().expect("cache lock poisoned").clear()
+    }
+
+    pub(crate) fn contains(&self, key: &CacheKey) -> bool {
+        self.entries
+            .lock()
+            .expect("cache lock poisoned")
+            .get(key)
+            .is_some()
+    }
+
+    pub(crate) fn insert(&self, key: CacheKey) {
+        self.entries
+            .lock()
+            .expect("cache lock poisoned")
+            .put(key, ());
+    }
+}
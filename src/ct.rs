@@ -0,0 +1,290 @@
+//! Constant-time fixed-base scalar multiplication and compression.
+//!
+//! The `decaf377` crate only provides variable-time scalar multiplication and
+//! compression, which is fine for verification but not for operations on
+//! secret scalars. This module implements the small amount of curve arithmetic
+//! needed to compute `[s]B` and its encoding without secret-dependent branches
+//! or memory accesses:
+//!
+//! - points are kept in extended twisted Edwards coordinates, using the
+//!   complete addition and doubling formulas from [HWCD08];
+//! - the scalar is processed in fixed 4-bit windows, with every table entry
+//!   read on every lookup, and entries selected limb by limb;
+//! - before compression the point is normalized to affine coordinates using a
+//!   fixed exponentiation, so that the only variable-time step (the inverse
+//!   square root) operates on a value determined by the public result alone.
+//!
+//! Only the final encoding is passed back to `decaf377`, and decompressing it
+//! is safe because it is public.
+//!
+//! [HWCD08]: https://eprint.iacr.org/2008/522
+
+use decaf377::{Fq, Fr};
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq};
+
+use crate::Domain;
+
+/// The Edwards curve parameter `d`; the parameter `a` is `-1`.
+fn coeff_d() -> Fq {
+    Fq::from(3021u32)
+}
+
+/// Returns `a - d`.
+fn a_minus_d() -> Fq {
+    -Fq::from(3022u32)
+}
+
+#[cfg(feature = "std")]
+fn sqrt_ratio_zeta(num: &Fq, den: &Fq) -> (bool, Fq) {
+    Fq::sqrt_ratio_zeta(num, den)
+}
+
+#[cfg(not(feature = "std"))]
+fn sqrt_ratio_zeta(num: &Fq, den: &Fq) -> (bool, Fq) {
+    Fq::non_arkworks_sqrt_ratio_zeta(num, den)
+}
+
+fn is_negative(x: &Fq) -> Choice {
+    Choice::from(x.to_bytes_le()[0] & 1)
+}
+
+// `Fq`'s own `ConditionallySelectable` implementation does not preserve the
+// Montgomery representation on the arkworks backend, so selections are either
+// computed arithmetically, by multiplying with a 0 or 1 mask, or performed on
+// the raw Montgomery limbs of precomputed values.
+
+fn ct_abs(x: Fq) -> Fq {
+    let mask = Fq::from(is_negative(&x).unwrap_u8());
+    x - (x + x) * mask
+}
+
+/// Inverts `x` by raising it to the power `q - 2`.
+///
+/// The exponent is public, so this runs in constant time; a zero input maps
+/// to zero.
+fn ct_invert(x: &Fq) -> Fq {
+    let mut exp = Fq::MODULUS_LIMBS;
+    exp[0] -= 2;
+
+    let mut acc = Fq::ONE;
+    for limb in exp.iter().rev() {
+        for i in (0..64).rev() {
+            acc = acc.square();
+            if (limb >> i) & 1 == 1 {
+                acc *= x;
+            }
+        }
+    }
+    acc
+}
+
+/// A point in extended twisted Edwards coordinates.
+#[derive(Copy, Clone)]
+struct Point {
+    x: Fq,
+    y: Fq,
+    z: Fq,
+    t: Fq,
+}
+
+impl Point {
+    const IDENTITY: Point = Point {
+        x: Fq::ZERO,
+        y: Fq::ONE,
+        z: Fq::ONE,
+        t: Fq::ZERO,
+    };
+
+    /// Decompress a public encoding, in variable time.
+    fn vartime_decompress(bytes: &[u8; 32]) -> Option<Point> {
+        let s = Fq::from_bytes_checked(bytes).ok()?;
+        if bool::from(is_negative(&s)) {
+            return None;
+        }
+
+        let ss = s.square();
+        let u_1 = Fq::ONE - ss;
+        let u_2 = u_1.square() - Fq::from(4u32) * coeff_d() * ss;
+        let (was_square, mut v) = sqrt_ratio_zeta(&Fq::ONE, &(u_2 * u_1.square()));
+        if !was_square {
+            return None;
+        }
+
+        let two_s_u_1 = (Fq::ONE + Fq::ONE) * s * u_1;
+        if bool::from(is_negative(&(two_s_u_1 * v))) {
+            v = -v;
+        }
+
+        let x = two_s_u_1 * v.square() * u_2;
+        let y = (Fq::ONE + ss) * v * u_1;
+        Some(Point {
+            x,
+            y,
+            z: Fq::ONE,
+            t: x * y,
+        })
+    }
+
+    fn add(&self, other: &Point) -> Point {
+        // https://eprint.iacr.org/2008/522 Section 3.1
+        let a = self.x * other.x;
+        let b = self.y * other.y;
+        let c = coeff_d() * self.t * other.t;
+        let d = self.z * other.z;
+        let e = (self.x + self.y) * (other.x + other.y) - a - b;
+        let f = d - c;
+        let g = d + c;
+        // Since a = -1, H = B - aA = B + A.
+        let h = b + a;
+        Point {
+            x: e * f,
+            y: g * h,
+            z: f * g,
+            t: e * h,
+        }
+    }
+
+    fn double(&self) -> Point {
+        // https://eprint.iacr.org/2008/522 Section 3.3
+        let a = self.x.square();
+        let b = self.y.square();
+        let c = self.z.square() + self.z.square();
+        let d = -a;
+        let e = (self.x + self.y).square() - a - b;
+        let g = d + b;
+        let f = g - c;
+        let h = d - b;
+        Point {
+            x: e * f,
+            y: g * h,
+            z: f * g,
+            t: e * h,
+        }
+    }
+
+    /// Compress this point, in constant time with respect to its
+    /// representation.
+    fn compress(&self) -> [u8; 32] {
+        let z_inv = ct_invert(&self.z);
+        let x = self.x * z_inv;
+        let y = self.y * z_inv;
+        let t = x * y;
+
+        // This follows the decaf377 encoding with Z = 1. Both `u_1` and `x^2`
+        // are unchanged by adding the 2-torsion point, so the input to the
+        // inverse square root depends only on the encoded element.
+        let u_1 = (x + t) * (x - t);
+        let (_always_square, v) = sqrt_ratio_zeta(&Fq::ONE, &(u_1 * a_minus_d() * x.square()));
+        let u_2 = ct_abs(v * u_1);
+        let u_3 = u_2 - t;
+        ct_abs(a_minus_d() * v * u_3 * x).to_bytes_le()
+    }
+}
+
+/// Returns the Montgomery limbs of `x`, that is, the canonical limbs of
+/// `x * 2^256`.
+fn montgomery_limbs(x: &Fq) -> [u64; 4] {
+    let r = Fq::from(1u64 << 32).square().square().square();
+    let bytes = (*x * r).to_bytes_le();
+    let mut limbs = [0u64; 4];
+    for (limb, chunk) in limbs.iter_mut().zip(bytes.chunks_exact(8)) {
+        *limb = u64::from_le_bytes(chunk.try_into().expect("chunks have 8 bytes"));
+    }
+    limbs
+}
+
+/// The multiples `[0]P, ..., [15]P` of a public point `P`, with coordinates
+/// stored as Montgomery limbs so that entries can be selected in constant time
+/// without any field arithmetic.
+struct Table([[[u64; 4]; 4]; 16]);
+
+impl Table {
+    fn new(point: &Point) -> Table {
+        let mut table = [[[0u64; 4]; 4]; 16];
+        let mut multiple = Point::IDENTITY;
+        for entry in table.iter_mut() {
+            *entry = [
+                montgomery_limbs(&multiple.x),
+                montgomery_limbs(&multiple.y),
+                montgomery_limbs(&multiple.z),
+                montgomery_limbs(&multiple.t),
+            ];
+            multiple = multiple.add(point);
+        }
+        Table(table)
+    }
+
+    /// Returns `[index]P`, reading every entry of the table.
+    fn lookup(&self, index: u8) -> Point {
+        let mut out = [[0u64; 4]; 4];
+        for (j, entry) in self.0.iter().enumerate() {
+            let choice = index.ct_eq(&(j as u8));
+            for (out_coord, coord) in out.iter_mut().zip(entry) {
+                for (out_limb, limb) in out_coord.iter_mut().zip(coord) {
+                    out_limb.conditional_assign(limb, choice);
+                }
+            }
+        }
+        Point {
+            x: Fq::from_montgomery_limbs(out[0]),
+            y: Fq::from_montgomery_limbs(out[1]),
+            z: Fq::from_montgomery_limbs(out[2]),
+            t: Fq::from_montgomery_limbs(out[3]),
+        }
+    }
+
+    /// Compute `[s]P` in constant time with respect to `s`.
+    fn mul(&self, s: &Fr) -> Point {
+        let bytes = s.to_bytes();
+        let mut acc = Point::IDENTITY;
+        for i in (0..64).rev() {
+            let window = (bytes[i / 2] >> (4 * (i % 2))) & 0x0f;
+            acc = acc.double().double().double().double();
+
+            acc = acc.add(&self.lookup(window));
+        }
+        acc
+    }
+}
+
+fn basepoint<D: Domain>() -> Point {
+    Point::vartime_decompress(&D::basepoint().vartime_compress().0)
+        .expect("basepoint encodings are valid")
+}
+
+/// Returns the table of multiples of the basepoint of `D`, which is computed
+/// once per domain.
+#[cfg(feature = "std")]
+fn basepoint_table<D: Domain>() -> &'static Table {
+    use std::sync::OnceLock;
+
+    static SPEND_AUTH: OnceLock<Table> = OnceLock::new();
+    static BINDING: OnceLock<Table> = OnceLock::new();
+
+    let table = if D::TAG == <crate::SpendAuth as crate::domain::Sealed>::TAG {
+        &SPEND_AUTH
+    } else {
+        &BINDING
+    };
+    table.get_or_init(|| Table::new(&basepoint::<D>()))
+}
+
+/// Returns the table of multiples of the basepoint of `D`.
+#[cfg(not(feature = "std"))]
+fn basepoint_table<D: Domain>() -> Table {
+    Table::new(&basepoint::<D>())
+}
+
+/// Returns the encoding of `[s]B_D`, in constant time with respect to `s`.
+pub(crate) fn basepoint_mul<D: Domain>(s: &Fr) -> [u8; 32] {
+    basepoint_table::<D>().mul(s).compress()
+}
+
+/// Returns the encoding of `P + [s]B_D`, where `P` is given by its (public)
+/// encoding, in constant time with respect to `s`.
+pub(crate) fn add_basepoint_mul<D: Domain>(point: &[u8; 32], s: &Fr) -> [u8; 32] {
+    Point::vartime_decompress(point)
+        .expect("point encodings are valid")
+        .add(&basepoint_table::<D>().mul(s))
+        .compress()
+}
@@ -0,0 +1,215 @@
+//! Seed-based and hierarchical key derivation.
+//!
+//! [`SigningKey::from_seed`] derives a signing key for any domain from a seed
+//! of at least 32 bytes, using BLAKE2b-512 with the personalization
+//! `decaf377-rdsa-kd`, keeping domains separate.
+//!
+//! [`ExtendedSigningKey`] and [`ExtendedVerificationKey`] implement a
+//! BIP32-style hierarchy of `SpendAuth` keys. Each extended key carries a
+//! 32-byte chain code, and the child at index `i` is obtained by randomizing
+//! the parent key with a tweak derived from the chain code, `i`, and either
+//! the parent signing key (for hardened indices, at least [`HARDENED`]) or the
+//! parent verification key (for other indices). Non-hardened children can
+//! therefore be derived from an [`ExtendedVerificationKey`] alone, using
+//! [`VerificationKey::randomize`], while hardened children require the
+//! signing key.
+//!
+//! Concretely, with `H_k` denoting BLAKE2b-512 keyed with the parent chain
+//! code `k`, and `i` encoded as 4 little-endian bytes,
+//!
+//! ```text
+//! data     = 0x00 || sk || i   (hardened)
+//!          = vk || i           (non-hardened)
+//! tweak    = H_k(0x01 || data) mod r
+//! child_cc = H_k(0x02 || data)[0..32]
+//! ```
+
+use blake2b_simd::{Params, State};
+use decaf377::Fr;
+use zeroize::Zeroize;
+
+use crate::{domain::Sealed, Domain, Error, SigningKey, SpendAuth, VerificationKey};
+
+/// The first hardened child index.
+pub const HARDENED: u32 = 1 << 31;
+
+/// The minimum length of a seed, in bytes.
+pub const MIN_SEED_LEN: usize = 32;
+
+const PERSONAL: &[u8; 16] = b"decaf377-rdsa-kd";
+
+fn hasher(chain_code: Option<&[u8; 32]>) -> State {
+    let mut params = Params::new();
+    params.hash_length(64).personal(PERSONAL);
+    if let Some(key) = chain_code {
+        params.key(key);
+    }
+    params.to_state()
+}
+
+fn from_seed_inner(label: &[u8], tag: u8, seed: &[u8]) -> Result<[u8; 64], Error> {
+    if seed.len() < MIN_SEED_LEN {
+        return Err(Error::InvalidSeed);
+    }
+    Ok(*hasher(None)
+        .update(label)
+        .update(&[tag])
+        .update(seed)
+        .finalize()
+        .as_array())
+}
+
+impl<D: Domain> SigningKey<D> {
+    /// Derive a signing key for domain `D` from `seed`.
+    ///
+    /// The seed must be at least [`MIN_SEED_LEN`] bytes long, and should be
+    /// generated securely; the same seed produces unrelated keys in each
+    /// domain.
+    pub fn from_seed(seed: &[u8]) -> Result<Self, Error> {
+        let mut wide = from_seed_inner(b"signing key", D::TAG, seed)?;
+        let sk = Fr::from_le_bytes_mod_order(&wide[..]);
+        wide.zeroize();
+        Ok(SigningKey::new_from_field(sk))
+    }
+}
+
+/// Computes the tweak and child chain code for `data`.
+fn derive(chain_code: &[u8; 32], data: &[u8], index: u32) -> (Fr, [u8; 32]) {
+    let index = index.to_le_bytes();
+    let mut wide = *hasher(Some(chain_code))
+        .update(&[1])
+        .update(data)
+        .update(&index)
+        .finalize()
+        .as_array();
+    let tweak = Fr::from_le_bytes_mod_order(&wide[..]);
+    wide.zeroize();
+
+    let mut child_chain_code = [0u8; 32];
+    child_chain_code.copy_from_slice(
+        &hasher(Some(chain_code))
+            .update(&[2])
+            .update(data)
+            .update(&index)
+            .finalize()
+            .as_bytes()[..32],
+    );
+    (tweak, child_chain_code)
+}
+
+/// A `SpendAuth` signing key with a chain code, for hierarchical derivation.
+#[derive(Copy, Clone)]
+pub struct ExtendedSigningKey {
+    signing_key: SigningKey<SpendAuth>,
+    chain_code: [u8; 32],
+}
+
+impl ExtendedSigningKey {
+    /// Derive the master extended signing key from `seed`.
+    ///
+    /// The seed must be at least [`MIN_SEED_LEN`] bytes long. The master
+    /// signing key is the same as [`SigningKey::from_seed`].
+    pub fn from_seed(seed: &[u8]) -> Result<Self, Error> {
+        let signing_key = SigningKey::from_seed(seed)?;
+        let mut wide = from_seed_inner(b"chain code", SpendAuth::TAG, seed)?;
+        let mut chain_code = [0u8; 32];
+        chain_code.copy_from_slice(&wide[..32]);
+        wide.zeroize();
+        Ok(Self {
+            signing_key,
+            chain_code,
+        })
+    }
+
+    /// Returns the signing key.
+    pub fn signing_key(&self) -> SigningKey<SpendAuth> {
+        self.signing_key
+    }
+
+    /// Returns the chain code.
+    pub fn chain_code(&self) -> [u8; 32] {
+        self.chain_code
+    }
+
+    /// Returns the corresponding extended verification key.
+    pub fn to_extended_verification_key(&self) -> ExtendedVerificationKey {
+        ExtendedVerificationKey {
+            verification_key: self.signing_key.pk,
+            chain_code: self.chain_code,
+        }
+    }
+
+    /// Derive the child key at `index`, which is hardened if it is at least
+    /// [`HARDENED`].
+    pub fn derive_child(&self, index: u32) -> Self {
+        let (tweak, chain_code) = if index >= HARDENED {
+            let mut data = [0u8; 33];
+            data[1..].copy_from_slice(&self.signing_key.to_bytes());
+            let derived = derive(&self.chain_code, &data, index);
+            data.zeroize();
+            derived
+        } else {
+            derive(&self.chain_code, &self.signing_key.pk.to_bytes(), index)
+        };
+        Self {
+            signing_key: self.signing_key.randomize(&tweak),
+            chain_code,
+        }
+    }
+
+    /// Derive the descendant key along `path`.
+    pub fn derive_path(&self, path: &[u32]) -> Self {
+        path.iter()
+            .fold(*self, |key, index| key.derive_child(*index))
+    }
+}
+
+/// A `SpendAuth` verification key with a chain code, for deriving
+/// non-hardened children.
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub struct ExtendedVerificationKey {
+    verification_key: VerificationKey<SpendAuth>,
+    chain_code: [u8; 32],
+}
+
+impl ExtendedVerificationKey {
+    /// Construct an extended verification key from its parts.
+    pub fn new(verification_key: VerificationKey<SpendAuth>, chain_code: [u8; 32]) -> Self {
+        Self {
+            verification_key,
+            chain_code,
+        }
+    }
+
+    /// Returns the verification key.
+    pub fn verification_key(&self) -> VerificationKey<SpendAuth> {
+        self.verification_key
+    }
+
+    /// Returns the chain code.
+    pub fn chain_code(&self) -> [u8; 32] {
+        self.chain_code
+    }
+
+    /// Derive the non-hardened child key at `index`.
+    ///
+    /// Returns [`Error::HardenedDerivation`] if `index` is hardened.
+    pub fn derive_child(&self, index: u32) -> Result<Self, Error> {
+        if index >= HARDENED {
+            return Err(Error::HardenedDerivation);
+        }
+        let (tweak, chain_code) =
+            derive(&self.chain_code, &self.verification_key.to_bytes(), index);
+        Ok(Self {
+            verification_key: self.verification_key.randomize(&tweak),
+            chain_code,
+        })
+    }
+
+    /// Derive the descendant key along `path`, which must not contain
+    /// hardened indices.
+    pub fn derive_path(&self, path: &[u32]) -> Result<Self, Error> {
+        path.iter()
+            .try_fold(*self, |key, index| key.derive_child(*index))
+    }
+}
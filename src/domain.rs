@@ -24,23 +24,43 @@ impl Domain for SpendAuth {}
 pub(crate) mod private {
     use super::*;
 
-    fn hash_to_group(input: &[u8]) -> decaf377::Element {
+    pub(crate) fn hash_to_group(input: &[u8]) -> decaf377::Element {
         decaf377::Element::encode_to_curve(&decaf377::Fq::from_le_bytes_mod_order(
             blake2b_simd::blake2b(input).as_bytes(),
         ))
     }
 
     pub trait Sealed: Copy + Clone + Eq + PartialEq + core::fmt::Debug {
+        /// A byte identifying this domain in serialized formats.
+        const TAG: u8;
+
+        /// The OID identifying keys in this domain in PKCS#8 and SPKI encodings.
+        ///
+        /// These OIDs are provisional (see `SPEND_AUTH_OID`), but appear in stored
+        /// keys, so decoding must keep accepting them if they are replaced.
+        #[cfg(feature = "pkcs8")]
+        const OID: pkcs8::ObjectIdentifier;
+
         fn basepoint() -> decaf377::Element;
     }
 
     impl Sealed for Binding {
+        const TAG: u8 = 2;
+        #[cfg(feature = "pkcs8")]
+        const OID: pkcs8::ObjectIdentifier =
+            pkcs8::ObjectIdentifier::new_unwrap("1.3.6.1.3.377.1.2");
+
         fn basepoint() -> decaf377::Element {
             hash_to_group(b"decaf377-rdsa-binding")
         }
     }
 
     impl Sealed for SpendAuth {
+        const TAG: u8 = 1;
+        #[cfg(feature = "pkcs8")]
+        const OID: pkcs8::ObjectIdentifier =
+            pkcs8::ObjectIdentifier::new_unwrap("1.3.6.1.3.377.1.1");
+
         fn basepoint() -> decaf377::Element {
             decaf377::Element::GENERATOR
         }
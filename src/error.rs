@@ -11,6 +11,41 @@ pub enum Error {
     InvalidSignature,
     /// Occurs when reading from a slice of the wrong length.
     WrongSliceLength { expected: usize, found: usize },
+    /// An encoding was for a different signature domain than expected.
+    WrongDomain,
+    /// The encoding of an encrypted key file was malformed.
+    MalformedKeyFile,
+    /// The key derivation parameters were out of range.
+    InvalidKdfParams,
+    /// Decryption of an encrypted key file failed.
+    DecryptionFailed,
+    /// The batch verification service has shut down.
+    ServiceClosed,
+    /// A freshly created signature failed verification, indicating a fault
+    /// during signing.
+    FaultDetected,
+    /// A presignature was used with a different signing key than the one that
+    /// created it.
+    WrongPresignature,
+    /// The threshold for splitting a signing key was out of range.
+    InvalidThreshold,
+    /// A secret share was malformed, failed verification, or did not
+    /// reconstruct the expected signing key.
+    InvalidShare,
+    /// A seed for key derivation was too short.
+    InvalidSeed,
+    /// A hardened child key was requested from a verification key.
+    HardenedDerivation,
+    /// The encoding of a randomizer was malformed.
+    MalformedRandomizer,
+    /// A randomization proof was malformed or failed verification.
+    InvalidProof,
+    /// The encoding of a batch verification item or batch was malformed, or
+    /// had an unsupported version.
+    MalformedBatchItem,
+    /// Streaming batch verification failed in the chunk with index `chunk`,
+    /// at the item with index `item` in the stream if it was located.
+    ChunkVerificationFailed { chunk: usize, item: Option<usize> },
 }
 
 impl fmt::Display for Error {
@@ -25,6 +60,33 @@ impl fmt::Display for Error {
                 f.write_str(", found ")?;
                 found.fmt(f)
             }
+            Self::WrongDomain => f.write_str("Encoding is for the wrong signature domain."),
+            Self::MalformedKeyFile => f.write_str("Malformed encrypted key file."),
+            Self::InvalidKdfParams => f.write_str("Invalid key derivation parameters."),
+            Self::DecryptionFailed => f.write_str("Decryption of encrypted key file failed."),
+            Self::ServiceClosed => f.write_str("Batch verification service has shut down."),
+            Self::FaultDetected => f.write_str("Signature failed verification after signing."),
+            Self::WrongPresignature => {
+                f.write_str("Presignature was created for a different signing key.")
+            }
+            Self::InvalidThreshold => f.write_str("Invalid secret sharing threshold."),
+            Self::InvalidShare => f.write_str("Invalid or insufficient secret shares."),
+            Self::InvalidSeed => f.write_str("Key derivation seed is too short."),
+            Self::HardenedDerivation => {
+                f.write_str("Hardened keys cannot be derived from a verification key.")
+            }
+            Self::MalformedRandomizer => f.write_str("Malformed randomizer encoding."),
+            Self::InvalidProof => f.write_str("Invalid randomization proof."),
+            Self::MalformedBatchItem => f.write_str("Malformed batch item encoding."),
+            Self::ChunkVerificationFailed { chunk, item } => {
+                f.write_str("Batch verification failed in chunk ")?;
+                chunk.fmt(f)?;
+                if let Some(item) = item {
+                    f.write_str(", at item ")?;
+                    item.fmt(f)?;
+                }
+                Ok(())
+            }
         }
     }
 }
@@ -8,15 +8,18 @@ pub struct HStar {
 
 impl Default for HStar {
     fn default() -> Self {
-        let state = Params::new()
-            .hash_length(64)
-            .personal(b"decaf377-rdsa---")
-            .to_state();
-        Self { state }
+        Self::new(b"decaf377-rdsa---")
     }
 }
 
 impl HStar {
+    /// Create a hash-to-scalar function with the given `personal`ization, to
+    /// separate uses other than signing from signature challenges.
+    pub(crate) fn new(personal: &[u8; 16]) -> Self {
+        let state = Params::new().hash_length(64).personal(personal).to_state();
+        Self { state }
+    }
+
     /// Add `data` to the hash, and return `Self` for chaining.
     pub fn update(&mut self, data: impl AsRef<[u8]>) -> &mut Self {
         self.state.update(data.as_ref());
@@ -0,0 +1,135 @@
+//! A process-wide cache of decompressed verification keys.
+//!
+//! With the `key_cache` feature, `VerificationKey::try_from` consults a cache
+//! of decompressed verification keys, keyed by their domain and encoding, and
+//! so do batch verification and
+//! [`Item::verify_single`](crate::batch::Item::verify_single). It holds up to
+//! [`DEFAULT_CAPACITY`] keys unless resized with [`set_capacity`], and only
+//! valid keys are cached.
+//!
+//! Keys derived from signing keys bypass the cache, so that its hits and
+//! misses do not reveal which signing keys have been used. Randomized keys
+//! bypass it too, since they are used once and would only evict other keys.
+//!
+//! The cache is split into shards, each behind its own lock, so that parallel
+//! verifications rarely contend. A lookup takes a single lock, which is held
+//! while a missing key is decompressed.
+
+use std::{
+    num::NonZeroUsize,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex, MutexGuard,
+    },
+};
+
+use decaf377::Element;
+use lru::LruCache;
+
+use crate::{Domain, Error};
+
+/// The default number of verification keys held by the cache.
+pub const DEFAULT_CAPACITY: usize = 1024;
+
+/// The number of independently locked shards.
+const SHARDS: usize = 16;
+
+/// One shard of the cache.
+struct Shard {
+    capacity: usize,
+    entries: Option<LruCache<(u8, [u8; 32]), Element>>,
+}
+
+impl Shard {
+    /// Returns the shard entries, creating them on first use, or `None` if
+    /// the shard is disabled.
+    fn entries(&mut self) -> Option<&mut LruCache<(u8, [u8; 32]), Element>> {
+        let capacity = NonZeroUsize::new(self.capacity)?;
+        Some(self.entries.get_or_insert_with(|| LruCache::new(capacity)))
+    }
+
+    fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        match NonZeroUsize::new(capacity) {
+            Some(capacity) => {
+                if let Some(entries) = self.entries.as_mut() {
+                    entries.resize(capacity);
+                }
+            }
+            None => self.entries = None,
+        }
+    }
+}
+
+static CAPACITY: AtomicUsize = AtomicUsize::new(DEFAULT_CAPACITY);
+
+static CACHE: [Mutex<Shard>; SHARDS] = [const {
+    Mutex::new(Shard {
+        capacity: DEFAULT_CAPACITY / SHARDS,
+        entries: None,
+    })
+}; SHARDS];
+
+/// Returns the capacity of shard `index` when the cache holds `capacity` keys.
+fn shard_capacity(capacity: usize, index: usize) -> usize {
+    capacity / SHARDS + usize::from(index < capacity % SHARDS)
+}
+
+fn lock(shard: &Mutex<Shard>) -> MutexGuard<'_, Shard> {
+    shard.lock().expect("cache lock poisoned")
+}
+
+/// Set the number of verification keys held by the cache, evicting the least
+/// recently used keys if it shrinks. A capacity of zero disables the cache.
+pub fn set_capacity(capacity: usize) {
+    CAPACITY.store(capacity, Ordering::Relaxed);
+    for (index, shard) in CACHE.iter().enumerate() {
+        lock(shard).set_capacity(shard_capacity(capacity, index));
+    }
+}
+
+/// Returns the number of verification keys the cache can hold.
+pub fn capacity() -> usize {
+    CAPACITY.load(Ordering::Relaxed)
+}
+
+/// Returns the number of verification keys in the cache.
+pub fn len() -> usize {
+    CACHE
+        .iter()
+        .map(|shard| lock(shard).entries.as_ref().map_or(0, LruCache::len))
+        .sum()
+}
+
+/// Remove all verification keys from the cache.
+pub fn clear() {
+    for shard in CACHE.iter() {
+        if let Some(entries) = lock(shard).entries.as_mut() {
+            entries.clear();
+        }
+    }
+}
+
+/// Decompress the verification key encoded by `bytes` with `decompress`,
+/// consulting and populating the cache.
+pub(crate) fn decompress_cached<D: Domain>(
+    bytes: [u8; 32],
+    decompress: impl FnOnce([u8; 32]) -> Result<Element, Error>,
+) -> Result<Element, Error> {
+    // The low bit of an encoding is always zero, but its second byte is close
+    // to uniform for valid keys, so it spreads keys evenly among the shards.
+    let index = (usize::from(bytes[1]) + usize::from(D::TAG)) % SHARDS;
+    let mut shard = lock(&CACHE[index]);
+    let Some(entries) = shard.entries() else {
+        drop(shard);
+        return decompress(bytes);
+    };
+
+    let key = (D::TAG, bytes);
+    if let Some(point) = entries.get(&key) {
+        return Ok(*point);
+    }
+    let point = decompress(bytes)?;
+    entries.put(key, point);
+    Ok(point)
+}
@@ -0,0 +1,217 @@
+//! Password-encrypted signing key files.
+//!
+//! A key file stores a [`SigningKey`] encrypted under a key derived from a
+//! password with Argon2id, using XChaCha20-Poly1305. The header is left in the
+//! clear, so that the domain and verification key of a stored key can be
+//! inspected without the password, but it is authenticated as associated data.
+//!
+//! Version 1 of the format is laid out as follows, with integers encoded in
+//! little-endian order:
+//!
+//! | Offset | Length | Contents                                    |
+//! |--------|--------|---------------------------------------------|
+//! | 0      | 8      | The magic bytes `d377rdsa`                  |
+//! | 8      | 1      | The format version, `1`                     |
+//! | 9      | 1      | The domain tag (`1` SpendAuth, `2` Binding) |
+//! | 10     | 32     | The verification key                        |
+//! | 42     | 4      | The Argon2id memory cost, in KiB            |
+//! | 46     | 4      | The Argon2id number of iterations           |
+//! | 50     | 4      | The Argon2id degree of parallelism          |
+//! | 54     | 16     | The Argon2id salt                           |
+//! | 70     | 24     | The XChaCha20-Poly1305 nonce                |
+//! | 94     | 32     | The encrypted signing key                   |
+//! | 126    | 16     | The Poly1305 authentication tag             |
+//!
+//! The header is only authenticated once the key has been derived, so the
+//! KDF parameters it records are checked against upper bounds before any work
+//! is done, to prevent a crafted file from demanding unbounded memory or time.
+//! [`SigningKey::from_encrypted`] accepts at most [`KdfParams::default_limits`],
+//! and [`SigningKey::from_encrypted_with_limits`] accepts caller-chosen bounds.
+
+use alloc::vec::Vec;
+
+use argon2::{Algorithm, Argon2, Params, Version};
+use chacha20poly1305::{
+    aead::{AeadInPlace, KeyInit},
+    Tag, XChaCha20Poly1305, XNonce,
+};
+use rand_core::{CryptoRng, RngCore};
+use zeroize::Zeroizing;
+
+use crate::{Domain, Error, SigningKey, VerificationKey};
+
+const MAGIC: [u8; 8] = *b"d377rdsa";
+const VERSION: u8 = 1;
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 24;
+const HEADER_LEN: usize = 8 + 1 + 1 + 32 + 12 + SALT_LEN + NONCE_LEN;
+const FILE_LEN: usize = HEADER_LEN + 32 + 16;
+
+/// Parameters for deriving the encryption key of a key file from a password.
+///
+/// The default parameters are the Argon2id defaults recommended by OWASP:
+/// 19 MiB of memory, 2 iterations and 1 degree of parallelism.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct KdfParams {
+    m_cost: u32,
+    t_cost: u32,
+    p_cost: u32,
+}
+
+impl Default for KdfParams {
+    fn default() -> Self {
+        Self {
+            m_cost: Params::DEFAULT_M_COST,
+            t_cost: Params::DEFAULT_T_COST,
+            p_cost: Params::DEFAULT_P_COST,
+        }
+    }
+}
+
+impl KdfParams {
+    /// Construct Argon2id parameters with the given memory cost (in KiB),
+    /// number of iterations, and degree of parallelism.
+    pub fn new(m_cost: u32, t_cost: u32, p_cost: u32) -> Result<Self, Error> {
+        Params::new(m_cost, t_cost, p_cost, Some(32)).map_err(|_| Error::InvalidKdfParams)?;
+        Ok(Self {
+            m_cost,
+            t_cost,
+            p_cost,
+        })
+    }
+
+    /// Returns the default upper bounds on the parameters accepted when
+    /// decrypting a key file: 1 GiB of memory, 16 iterations and 16 degrees of
+    /// parallelism.
+    pub fn default_limits() -> Self {
+        Self {
+            m_cost: 1 << 20,
+            t_cost: 16,
+            p_cost: 16,
+        }
+    }
+
+    /// Returns `true` if none of these parameters exceed those of `limits`.
+    pub fn is_within(&self, limits: &KdfParams) -> bool {
+        self.m_cost <= limits.m_cost && self.t_cost <= limits.t_cost && self.p_cost <= limits.p_cost
+    }
+
+    fn derive_key(&self, password: &[u8], salt: &[u8]) -> Result<Zeroizing<[u8; 32]>, Error> {
+        let params = Params::new(self.m_cost, self.t_cost, self.p_cost, Some(32))
+            .map_err(|_| Error::InvalidKdfParams)?;
+        let mut key = Zeroizing::new([0u8; 32]);
+        Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+            .hash_password_into(password, salt, &mut key[..])
+            .map_err(|_| Error::InvalidKdfParams)?;
+        Ok(key)
+    }
+}
+
+impl<D: Domain> SigningKey<D> {
+    /// Encrypt this signing key under `password`, using the default
+    /// [`KdfParams`].
+    pub fn to_encrypted<R: RngCore + CryptoRng>(&self, rng: R, password: &[u8]) -> Vec<u8> {
+        self.to_encrypted_with_params(rng, password, KdfParams::default())
+    }
+
+    /// Encrypt this signing key under `password`, using the supplied
+    /// [`KdfParams`].
+    pub fn to_encrypted_with_params<R: RngCore + CryptoRng>(
+        &self,
+        mut rng: R,
+        password: &[u8],
+        params: KdfParams,
+    ) -> Vec<u8> {
+        let mut salt = [0u8; SALT_LEN];
+        rng.fill_bytes(&mut salt);
+        let mut nonce = [0u8; NONCE_LEN];
+        rng.fill_bytes(&mut nonce);
+
+        let vk: VerificationKey<D> = self.into();
+
+        let mut file = Vec::with_capacity(FILE_LEN);
+        file.extend_from_slice(&MAGIC);
+        file.push(VERSION);
+        file.push(D::TAG);
+        file.extend_from_slice(&vk.to_bytes());
+        file.extend_from_slice(&params.m_cost.to_le_bytes());
+        file.extend_from_slice(&params.t_cost.to_le_bytes());
+        file.extend_from_slice(&params.p_cost.to_le_bytes());
+        file.extend_from_slice(&salt);
+        file.extend_from_slice(&nonce);
+        debug_assert_eq!(file.len(), HEADER_LEN);
+
+        let key = params
+            .derive_key(password, &salt)
+            .expect("KdfParams are validated on construction");
+        let mut sk_bytes = Zeroizing::new(self.to_bytes());
+        let tag = XChaCha20Poly1305::new(key.as_ref().into())
+            .encrypt_in_place_detached(XNonce::from_slice(&nonce), &file, &mut sk_bytes[..])
+            .expect("encryption of a 32-byte buffer cannot fail");
+
+        file.extend_from_slice(&sk_bytes[..]);
+        file.extend_from_slice(&tag);
+        file
+    }
+
+    /// Decrypt a signing key that was encrypted with [`SigningKey::to_encrypted`].
+    ///
+    /// This checks that the key file is for domain `D`, and that the decrypted
+    /// signing key matches the verification key recorded in the header.
+    /// Returns [`Error::InvalidKdfParams`] if the KDF parameters of the file
+    /// exceed [`KdfParams::default_limits`].
+    pub fn from_encrypted(file: &[u8], password: &[u8]) -> Result<Self, Error> {
+        Self::from_encrypted_with_limits(file, password, KdfParams::default_limits())
+    }
+
+    /// Decrypt a signing key as in [`SigningKey::from_encrypted`], accepting
+    /// KDF parameters up to `limits`.
+    pub fn from_encrypted_with_limits(
+        file: &[u8],
+        password: &[u8],
+        limits: KdfParams,
+    ) -> Result<Self, Error> {
+        if file.len() != FILE_LEN {
+            return Err(Error::WrongSliceLength {
+                expected: FILE_LEN,
+                found: file.len(),
+            });
+        }
+        let (header, body) = file.split_at(HEADER_LEN);
+        if header[0..8] != MAGIC || header[8] != VERSION {
+            return Err(Error::MalformedKeyFile);
+        }
+        if header[9] != D::TAG {
+            return Err(Error::WrongDomain);
+        }
+
+        let vk_bytes = &header[10..42];
+        let read_u32 = |i: usize| u32::from_le_bytes(header[i..i + 4].try_into().expect("4 bytes"));
+        let params = KdfParams::new(read_u32(42), read_u32(46), read_u32(50))
+            .map_err(|_| Error::MalformedKeyFile)?;
+        if !params.is_within(&limits) {
+            return Err(Error::InvalidKdfParams);
+        }
+        let salt = &header[54..54 + SALT_LEN];
+        let nonce = &header[54 + SALT_LEN..];
+
+        let key = params.derive_key(password, salt)?;
+        let mut sk_bytes = Zeroizing::new([0u8; 32]);
+        sk_bytes.copy_from_slice(&body[..32]);
+        XChaCha20Poly1305::new(key.as_ref().into())
+            .decrypt_in_place_detached(
+                XNonce::from_slice(nonce),
+                header,
+                &mut sk_bytes[..],
+                Tag::from_slice(&body[32..]),
+            )
+            .map_err(|_| Error::DecryptionFailed)?;
+
+        let sk = SigningKey::try_from(*sk_bytes)?;
+        let vk: VerificationKey<D> = (&sk).into();
+        if vk.to_bytes()[..] != *vk_bytes {
+            return Err(Error::MalformedKeyFile);
+        }
+        Ok(sk)
+    }
+}
@@ -1,22 +1,55 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 #![doc = include_str!("../README.md")]
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+mod ct;
 mod domain;
 mod error;
 mod hash;
 use hash::HStar;
+#[cfg(feature = "pkcs8")]
+mod pkcs;
+mod possession;
+mod presignature;
+mod randomizer;
 mod signature;
 
 mod signing_key;
+#[cfg(feature = "merlin")]
+mod transcript;
 mod verification_key;
 
 pub use domain::{Binding, Domain, SpendAuth};
 pub use error::Error;
+pub use possession::ProofOfPossession;
+pub use presignature::Presignature;
+pub use randomizer::Randomizer;
 pub use signature::Signature;
 pub use signing_key::SigningKey;
 pub use verification_key::{VerificationKey, VerificationKeyBytes};
 
 pub use decaf377::Fr;
 
-#[cfg(feature = "std")]
+#[cfg(feature = "merlin")]
+pub use merlin;
+#[cfg(feature = "pkcs8")]
+pub use pkcs::{BINDING_OID, SPEND_AUTH_OID};
+#[cfg(feature = "pkcs8")]
+pub use pkcs8;
+
 pub mod batch;
+#[cfg(feature = "cache")]
+pub mod cache;
+pub mod derivation;
+#[cfg(feature = "key_cache")]
+pub mod key_cache;
+#[cfg(feature = "encryption")]
+pub mod keyfile;
+#[cfg(feature = "alloc")]
+pub mod randomization;
+#[cfg(feature = "serde")]
+pub mod serde_fixed;
+#[cfg(feature = "alloc")]
+pub mod shamir;
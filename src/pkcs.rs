@@ -0,0 +1,128 @@
+//! PKCS#8 and SubjectPublicKeyInfo encodings of `decaf377-rdsa` keys.
+//!
+//! The encodings follow the structure that [RFC 8410] uses for Ed25519: the
+//! algorithm identifier carries an OID with absent parameters, the private key
+//! is a 32-byte `OCTET STRING` wrapped in the PKCS#8 `privateKey` field, and
+//! the public key is the 32-byte encoding of the verification key.
+//!
+//! Each signature domain has its own OID, and decoding rejects keys whose OID
+//! does not match the domain of the target type.
+//!
+//! The encoding and decoding methods, including PEM armor, come from the
+//! [`EncodePrivateKey`], [`DecodePrivateKey`](pkcs8::DecodePrivateKey),
+//! [`EncodePublicKey`] and [`DecodePublicKey`](pkcs8::DecodePublicKey) traits
+//! re-exported in [`crate::pkcs8`].
+//!
+//! [RFC 8410]: https://www.rfc-editor.org/rfc/rfc8410
+
+use pkcs8::{
+    der::asn1::BitStringRef,
+    spki::{self, AlgorithmIdentifierRef},
+    Document, EncodePrivateKey, EncodePublicKey, ObjectIdentifier, PrivateKeyInfo, SecretDocument,
+    SubjectPublicKeyInfoRef,
+};
+use zeroize::Zeroizing;
+
+use crate::{domain::Sealed, Binding, Domain, SigningKey, SpendAuth, VerificationKey};
+
+/// The OID identifying `decaf377-rdsa` keys in the `SpendAuth` domain.
+///
+/// This OID, `1.3.6.1.3.377.1.1`, is provisional and unstable: it is not
+/// registered, and the `377` sub-arc of the IANA `experimental` arc
+/// (`1.3.6.1.3`) has not been assigned to this project. It, like
+/// [`BINDING_OID`], may be replaced by an OID under a private-enterprise arc
+/// (`1.3.6.1.4.1`) controlled by Penumbra. Encoding would then use the new
+/// OID, and decoding would keep accepting this one, so that keys encoded now
+/// remain readable.
+pub const SPEND_AUTH_OID: ObjectIdentifier = <SpendAuth as Sealed>::OID;
+
+/// The OID identifying `decaf377-rdsa` keys in the `Binding` domain.
+///
+/// This OID, `1.3.6.1.3.377.1.2`, is provisional and unstable in the same way
+/// as [`SPEND_AUTH_OID`].
+pub const BINDING_OID: ObjectIdentifier = <Binding as Sealed>::OID;
+
+/// The DER tag and length of the `OCTET STRING` wrapping a 32-byte private key.
+const PRIVATE_KEY_HEADER: [u8; 2] = [0x04, 0x20];
+
+fn algorithm_identifier<D: Domain>() -> AlgorithmIdentifierRef<'static> {
+    AlgorithmIdentifierRef {
+        oid: D::OID,
+        parameters: None,
+    }
+}
+
+fn check_algorithm<D: Domain>(algorithm: &AlgorithmIdentifierRef<'_>) -> spki::Result<()> {
+    algorithm.assert_algorithm_oid(D::OID)?;
+    if algorithm.parameters.is_some() {
+        return Err(spki::Error::KeyMalformed);
+    }
+    Ok(())
+}
+
+impl<D: Domain> TryFrom<PrivateKeyInfo<'_>> for SigningKey<D> {
+    type Error = pkcs8::Error;
+
+    fn try_from(info: PrivateKeyInfo<'_>) -> pkcs8::Result<Self> {
+        check_algorithm::<D>(&info.algorithm)?;
+
+        let sk = match info.private_key {
+            [tag, len, rest @ ..] if [*tag, *len] == PRIVATE_KEY_HEADER => {
+                SigningKey::try_from(rest).map_err(|_| pkcs8::Error::KeyMalformed)?
+            }
+            _ => return Err(pkcs8::Error::KeyMalformed),
+        };
+
+        // If the (optional) public key is present, it must match the signing key.
+        if let Some(pk_bytes) = info.public_key {
+            let vk: VerificationKey<D> = (&sk).into();
+            if pk_bytes != &vk.to_bytes()[..] {
+                return Err(pkcs8::Error::KeyMalformed);
+            }
+        }
+
+        Ok(sk)
+    }
+}
+
+impl<D: Domain> EncodePrivateKey for SigningKey<D> {
+    fn to_pkcs8_der(&self) -> pkcs8::Result<SecretDocument> {
+        let mut private_key = Zeroizing::new([0u8; 34]);
+        private_key[..2].copy_from_slice(&PRIVATE_KEY_HEADER);
+        private_key[2..].copy_from_slice(&self.to_bytes());
+        let vk: VerificationKey<D> = self.into();
+        let pk_bytes = vk.to_bytes();
+
+        let info = PrivateKeyInfo {
+            algorithm: algorithm_identifier::<D>(),
+            private_key: &private_key[..],
+            public_key: Some(&pk_bytes),
+        };
+        SecretDocument::try_from(info)
+    }
+}
+
+impl<D: Domain> TryFrom<SubjectPublicKeyInfoRef<'_>> for VerificationKey<D> {
+    type Error = spki::Error;
+
+    fn try_from(info: SubjectPublicKeyInfoRef<'_>) -> spki::Result<Self> {
+        check_algorithm::<D>(&info.algorithm)?;
+
+        let pk_bytes = info
+            .subject_public_key
+            .as_bytes()
+            .ok_or(spki::Error::KeyMalformed)?;
+        VerificationKey::try_from(pk_bytes).map_err(|_| spki::Error::KeyMalformed)
+    }
+}
+
+impl<D: Domain> EncodePublicKey for VerificationKey<D> {
+    fn to_public_key_der(&self) -> spki::Result<Document> {
+        let pk_bytes = self.to_bytes();
+        let info = SubjectPublicKeyInfoRef {
+            algorithm: algorithm_identifier::<D>(),
+            subject_public_key: BitStringRef::new(0, &pk_bytes)?,
+        };
+        Document::try_from(info)
+    }
+}
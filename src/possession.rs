@@ -0,0 +1,107 @@
+use rand_core::{CryptoRng, RngCore};
+
+use decaf377::Fr;
+
+use crate::{Domain, Error, HStar, Signature, SigningKey, VerificationKey, VerificationKeyBytes};
+
+/// The personalization of the hash used for proofs of possession, distinct
+/// from the one used for signature challenges so that a proof can never be
+/// mistaken for a signature on a message, or vice versa.
+const PERSONAL: &[u8; 16] = b"decaf377-rdsa-po";
+
+/// Computes the challenge for a proof of possession with commitment `r_bytes`.
+pub(crate) fn challenge<D: Domain>(r_bytes: &[u8; 32], vk_bytes: &VerificationKeyBytes<D>) -> Fr {
+    HStar::new(PERSONAL)
+        .update(&r_bytes[..])
+        .update(&vk_bytes.bytes[..])
+        .finalize()
+}
+
+/// A proof of possession of the signing key for a verification key.
+///
+/// This is a Schnorr signature on the verification key itself, using a
+/// dedicated hash personalization. Requiring a proof of possession when keys
+/// are registered prevents rogue-key attacks, in which a key is chosen as a
+/// function of other participants' keys.
+#[derive(Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent, bound = "D: Domain"))]
+pub struct ProofOfPossession<D: Domain> {
+    pub(crate) signature: Signature<D>,
+}
+
+impl<D: Domain> ProofOfPossession<D> {
+    /// Returns the bytes of the proof.
+    pub fn to_bytes(&self) -> [u8; 64] {
+        self.signature.to_bytes()
+    }
+}
+
+impl<D: Domain> From<[u8; 64]> for ProofOfPossession<D> {
+    fn from(bytes: [u8; 64]) -> Self {
+        Self {
+            signature: bytes.into(),
+        }
+    }
+}
+
+impl<D: Domain> From<ProofOfPossession<D>> for [u8; 64] {
+    fn from(proof: ProofOfPossession<D>) -> [u8; 64] {
+        proof.to_bytes()
+    }
+}
+
+impl<D: Domain> TryFrom<&[u8]> for ProofOfPossession<D> {
+    type Error = Error;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        Ok(Self {
+            signature: bytes.try_into()?,
+        })
+    }
+}
+
+impl<D: Domain> SigningKey<D> {
+    /// Prove possession of this signing key, using the supplied `rng`.
+    pub fn prove_possession<R: RngCore + CryptoRng>(&self, mut rng: R) -> ProofOfPossession<D> {
+        let mut bonus_randomness = [0u8; 48];
+        rng.fill_bytes(&mut bonus_randomness);
+
+        let nonce = HStar::new(PERSONAL)
+            .update(&self.sk.to_bytes()[..])
+            .update(&bonus_randomness[..])
+            .update(&self.pk.bytes.bytes[..])
+            .finalize();
+
+        let r_bytes = crate::ct::basepoint_mul::<D>(&nonce);
+        let c = challenge(&r_bytes, &self.pk.bytes);
+        let s_bytes = (nonce + (c * self.sk)).to_bytes();
+
+        ProofOfPossession {
+            signature: Signature::from_parts(r_bytes, s_bytes),
+        }
+    }
+}
+
+impl<D: Domain> VerificationKey<D> {
+    /// Verify a proof of possession of the signing key for this verification
+    /// key.
+    pub fn verify_possession(&self, proof: &ProofOfPossession<D>) -> Result<(), Error> {
+        let c = challenge(&proof.signature.r_bytes(), &self.bytes);
+        self.verify_prehashed(&proof.signature, c)
+    }
+}
+
+#[cfg(feature = "alloc")]
+mod alloc_only {
+    use super::*;
+    use core::fmt;
+
+    impl<D: Domain> fmt::Debug for ProofOfPossession<D> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_tuple("ProofOfPossession")
+                .field(&hex::encode(self.to_bytes()))
+                .finish()
+        }
+    }
+}
@@ -0,0 +1,147 @@
+//! Presignatures, for signing with low latency.
+//!
+//! Most of the cost of signing is the fixed-base scalar multiplication that
+//! computes the commitment `R = [r]B` from the nonce `r`. Since the nonce need
+//! not depend on the message, it can be generated ahead of time: a
+//! [`Presignature`] holds a nonce and its commitment, and consuming it with
+//! [`SigningKey::sign_with_presignature`] only requires hashing and one scalar
+//! multiply-add.
+//!
+//! Reusing a nonce for two different messages reveals the signing key, so
+//! presignatures are single-use: they cannot be cloned or serialized, signing
+//! consumes them, and their nonce is zeroized when they are dropped. For the
+//! same reason, presignatures must be created with a working RNG.
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::fmt;
+
+use decaf377::Fr;
+use rand_core::{CryptoRng, RngCore};
+use zeroize::Zeroize;
+
+use crate::{Domain, Error, HStar, Signature, SigningKey, VerificationKeyBytes};
+
+/// The personalization of the hash used to derive presignature nonces,
+/// distinct from the one used for signing nonces so that a presignature never
+/// shares a nonce with a signature made by [`SigningKey::sign`], even if the
+/// RNG fails.
+const PERSONAL: &[u8; 16] = b"decaf377-rdsa-ps";
+
+/// A precomputed nonce and commitment for a single signature.
+///
+/// A presignature is bound to the signing key that created it, and can only
+/// be used once.
+pub struct Presignature<D: Domain> {
+    nonce: Fr,
+    r_bytes: [u8; 32],
+    vk_bytes: VerificationKeyBytes<D>,
+}
+
+impl<D: Domain> Presignature<D> {
+    /// Returns the verification key of the signing key this presignature was
+    /// created for.
+    pub fn verification_key_bytes(&self) -> VerificationKeyBytes<D> {
+        self.vk_bytes
+    }
+}
+
+impl<D: Domain> Drop for Presignature<D> {
+    fn drop(&mut self) {
+        self.nonce.zeroize();
+    }
+}
+
+impl<D: Domain> fmt::Debug for Presignature<D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Don't print the nonce.
+        f.debug_struct("Presignature")
+            .field("r_bytes", &self.r_bytes)
+            .field("vk_bytes", &self.vk_bytes.bytes)
+            .finish()
+    }
+}
+
+impl<D: Domain> SigningKey<D> {
+    /// Create a presignature for this signing key, using the supplied `rng`.
+    ///
+    /// Unlike [`SigningKey::sign`], which remains secure if the RNG fails,
+    /// this is only safe with a working RNG: the nonce cannot depend on the
+    /// message, so a failing RNG gives presignatures with the same nonce, and
+    /// signing two different messages with the same nonce reveals the signing
+    /// key.
+    pub fn presign<R: RngCore + CryptoRng>(&self, rng: R) -> Presignature<D> {
+        self.presign_with_index(rng, 0)
+    }
+
+    /// Create `n` presignatures for this signing key, using the supplied
+    /// `rng`.
+    ///
+    /// As with [`SigningKey::presign`], this is only safe with a working RNG.
+    /// The presignatures in one call have distinct nonces even if the RNG
+    /// fails, but may share them with presignatures from other calls.
+    #[cfg(feature = "alloc")]
+    pub fn presign_many<R: RngCore + CryptoRng>(
+        &self,
+        mut rng: R,
+        n: usize,
+    ) -> Vec<Presignature<D>> {
+        (0..n)
+            .map(|index| self.presign_with_index(&mut rng, index as u64))
+            .collect()
+    }
+
+    fn presign_with_index<R: RngCore + CryptoRng>(
+        &self,
+        mut rng: R,
+        index: u64,
+    ) -> Presignature<D> {
+        let mut bonus_randomness = [0u8; 48];
+        rng.fill_bytes(&mut bonus_randomness);
+
+        // As in `sign`, the signing key is hashed into the nonce, so that a
+        // weak RNG does not immediately compromise the key. There is no
+        // message to bind, so the randomness must not repeat.
+        let mut nonce = HStar::new(PERSONAL)
+            .update(&self.sk.to_bytes()[..])
+            .update(&bonus_randomness[..])
+            .update(&self.pk.bytes.bytes[..])
+            .update(index.to_le_bytes())
+            .finalize();
+        bonus_randomness.zeroize();
+
+        let r_bytes = crate::ct::basepoint_mul::<D>(&nonce);
+        let presig = Presignature {
+            nonce,
+            r_bytes,
+            vk_bytes: self.pk.bytes,
+        };
+        nonce.zeroize();
+        presig
+    }
+
+    /// Create a signature for domain `D` on `msg` using this `SigningKey` and
+    /// a `presignature` created by it.
+    ///
+    /// The presignature is consumed. Returns [`Error::WrongPresignature`] if it
+    /// was created by a different signing key.
+    pub fn sign_with_presignature(
+        &self,
+        presignature: Presignature<D>,
+        msg: &[u8],
+    ) -> Result<Signature<D>, Error> {
+        if presignature.vk_bytes != self.pk.bytes {
+            return Err(Error::WrongPresignature);
+        }
+
+        let c = HStar::default()
+            .update(&presignature.r_bytes[..])
+            .update(&self.pk.bytes.bytes[..])
+            .update(msg)
+            .finalize();
+
+        let s_bytes = (presignature.nonce + (c * self.sk)).to_bytes();
+
+        Ok(Signature::from_parts(presignature.r_bytes, s_bytes))
+    }
+}
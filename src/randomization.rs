@@ -0,0 +1,254 @@
+//! Zero-knowledge proofs that a key is a randomization of a committed key.
+//!
+//! Randomizing a `SpendAuth` verification key hides its link to the original
+//! key. Sometimes that link must be demonstrated to an auditor without being
+//! revealed: given a set of Pedersen [`KeyCommitment`]s `C_j = ak_j + [v_j]H`
+//! to base keys `ak_j`, a [`RandomizationProof`] shows that a randomized key
+//! `rk` equals `ak_i + [r]B` for some `i`, without revealing `i`, the
+//! randomizer `r`, or the commitment blinding factor `v_i`.
+//!
+//! Writing `X_j = rk - C_j`, the statement for each `j` is knowledge of `r` and
+//! `w = -v_j` such that `X_j = [r]B + [w]H`, where `B` is the `SpendAuth`
+//! basepoint and `H` is an independent generator. The proof is a
+//! Cramer-Damgård-Schoenmakers disjunction of Schnorr proofs for these
+//! statements, made non-interactive with the Fiat-Shamir transform over
+//! BLAKE2b-512 with the personalization `decaf377-rdsa-rp`. For a set of `n`
+//! commitments, the proof consists of `n` challenges and `2n` responses.
+//!
+//! Proving runs in variable time, and should be done on a trusted machine.
+
+use alloc::vec::Vec;
+
+use decaf377::{Element, Encoding, Fr};
+use rand_core::{CryptoRng, RngCore};
+
+use crate::{
+    domain::{private::hash_to_group, Sealed},
+    Error, HStar, Randomizer, SpendAuth, VerificationKey,
+};
+
+/// The independent generator used for key commitments.
+fn blinding_generator() -> Element {
+    hash_to_group(b"decaf377-rdsa-pedersen")
+}
+
+fn random_scalar<R: RngCore + CryptoRng>(rng: &mut R) -> Fr {
+    let mut bytes = [0u8; 64];
+    rng.fill_bytes(&mut bytes);
+    Fr::from_le_bytes_mod_order(&bytes[..])
+}
+
+/// A Pedersen commitment to a `SpendAuth` verification key.
+#[derive(Copy, Clone, Debug)]
+pub struct KeyCommitment {
+    point: Element,
+    bytes: [u8; 32],
+}
+
+impl KeyCommitment {
+    /// Commit to the verification key `vk` with the secret `blinding` factor.
+    pub fn new(vk: &VerificationKey<SpendAuth>, blinding: &Fr) -> Self {
+        let point = vk.point + blinding_generator() * blinding;
+        let bytes = point.vartime_compress().0;
+        Self { point, bytes }
+    }
+
+    /// Returns the byte encoding of the commitment.
+    pub fn to_bytes(&self) -> [u8; 32] {
+        self.bytes
+    }
+}
+
+impl PartialEq for KeyCommitment {
+    fn eq(&self, other: &Self) -> bool {
+        self.bytes == other.bytes
+    }
+}
+
+impl Eq for KeyCommitment {}
+
+impl TryFrom<[u8; 32]> for KeyCommitment {
+    type Error = Error;
+
+    fn try_from(bytes: [u8; 32]) -> Result<Self, Self::Error> {
+        let point = Encoding(bytes)
+            .vartime_decompress()
+            .map_err(|_| Error::InvalidProof)?;
+        Ok(Self { point, bytes })
+    }
+}
+
+impl From<KeyCommitment> for [u8; 32] {
+    fn from(commitment: KeyCommitment) -> [u8; 32] {
+        commitment.bytes
+    }
+}
+
+/// The challenge and responses for one commitment in a [`RandomizationProof`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+struct Branch {
+    e: Fr,
+    z_r: Fr,
+    z_w: Fr,
+}
+
+/// A proof that a randomized key derives from one of a set of committed keys.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RandomizationProof {
+    branches: Vec<Branch>,
+}
+
+/// Computes the Fiat-Shamir challenge for the proof transcript.
+fn challenge(rk: &VerificationKey<SpendAuth>, commitments: &[KeyCommitment], ts: &[Element]) -> Fr {
+    let mut hash = HStar::new(b"decaf377-rdsa-rp");
+    hash.update(rk.to_bytes())
+        .update((commitments.len() as u64).to_le_bytes());
+    for commitment in commitments {
+        hash.update(commitment.bytes);
+    }
+    for t in ts {
+        hash.update(t.vartime_compress().0);
+    }
+    hash.finalize()
+}
+
+/// Recomputes the commitment of a branch from its challenge and responses.
+fn branch_commitment(branch: &Branch, x: &Element) -> Element {
+    SpendAuth::basepoint() * branch.z_r + blinding_generator() * branch.z_w - x * branch.e
+}
+
+impl RandomizationProof {
+    /// Prove that `rk` is the key committed to by `commitments[index]`,
+    /// randomized by `randomizer`, where `blinding` is the blinding factor of
+    /// that commitment.
+    ///
+    /// Returns [`Error::InvalidProof`] if `index` is out of range or the
+    /// statement does not hold.
+    pub fn prove<R: RngCore + CryptoRng>(
+        mut rng: R,
+        rk: &VerificationKey<SpendAuth>,
+        commitments: &[KeyCommitment],
+        index: usize,
+        blinding: &Fr,
+        randomizer: &Randomizer,
+    ) -> Result<Self, Error> {
+        let r = randomizer.to_scalar();
+        let w = -*blinding;
+        let b = SpendAuth::basepoint();
+        let h = blinding_generator();
+
+        let xs: Vec<Element> = commitments.iter().map(|c| rk.point - c.point).collect();
+        match xs.get(index) {
+            Some(x) if *x == b * r + h * w => {}
+            _ => return Err(Error::InvalidProof),
+        }
+
+        // Simulate every other branch, and commit honestly on the real one.
+        let a_r = random_scalar(&mut rng);
+        let a_w = random_scalar(&mut rng);
+        let mut branches = Vec::with_capacity(commitments.len());
+        let mut ts = Vec::with_capacity(commitments.len());
+        for (j, x) in xs.iter().enumerate() {
+            if j == index {
+                branches.push(Branch {
+                    e: Fr::ZERO,
+                    z_r: Fr::ZERO,
+                    z_w: Fr::ZERO,
+                });
+                ts.push(b * a_r + h * a_w);
+            } else {
+                let branch = Branch {
+                    e: random_scalar(&mut rng),
+                    z_r: random_scalar(&mut rng),
+                    z_w: random_scalar(&mut rng),
+                };
+                ts.push(branch_commitment(&branch, x));
+                branches.push(branch);
+            }
+        }
+
+        let e = challenge(rk, commitments, &ts);
+        let e_real = branches.iter().fold(e, |acc, branch| acc - branch.e);
+        branches[index] = Branch {
+            e: e_real,
+            z_r: a_r + e_real * r,
+            z_w: a_w + e_real * w,
+        };
+
+        Ok(Self { branches })
+    }
+
+    /// Verify that `rk` is a randomization of one of the keys committed to by
+    /// `commitments`.
+    pub fn verify(
+        &self,
+        rk: &VerificationKey<SpendAuth>,
+        commitments: &[KeyCommitment],
+    ) -> Result<(), Error> {
+        if commitments.is_empty() || self.branches.len() != commitments.len() {
+            return Err(Error::InvalidProof);
+        }
+
+        let ts: Vec<Element> = self
+            .branches
+            .iter()
+            .zip(commitments)
+            .map(|(branch, c)| branch_commitment(branch, &(rk.point - c.point)))
+            .collect();
+
+        let e = self
+            .branches
+            .iter()
+            .fold(Fr::ZERO, |acc, branch| acc + branch.e);
+        if e == challenge(rk, commitments, &ts) {
+            Ok(())
+        } else {
+            Err(Error::InvalidProof)
+        }
+    }
+
+    /// Returns the byte encoding of the proof: the number of branches as 4
+    /// little-endian bytes, followed by the challenge and two responses of
+    /// each branch.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(4 + 96 * self.branches.len());
+        bytes.extend_from_slice(&(self.branches.len() as u32).to_le_bytes());
+        for branch in &self.branches {
+            bytes.extend_from_slice(&branch.e.to_bytes());
+            bytes.extend_from_slice(&branch.z_r.to_bytes());
+            bytes.extend_from_slice(&branch.z_w.to_bytes());
+        }
+        bytes
+    }
+}
+
+impl TryFrom<&[u8]> for RandomizationProof {
+    type Error = Error;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        if bytes.len() < 4 {
+            return Err(Error::InvalidProof);
+        }
+        let (n, body) = bytes.split_at(4);
+        let n = u32::from_le_bytes(n.try_into().expect("4 bytes")) as usize;
+        if body.len() / 96 != n || body.len() % 96 != 0 {
+            return Err(Error::InvalidProof);
+        }
+
+        let scalar = |bytes: &[u8]| {
+            Fr::from_bytes_checked(bytes.try_into().expect("32 bytes"))
+                .map_err(|_| Error::InvalidProof)
+        };
+        let branches = body
+            .chunks_exact(96)
+            .map(|chunk| {
+                Ok(Branch {
+                    e: scalar(&chunk[0..32])?,
+                    z_r: scalar(&chunk[32..64])?,
+                    z_w: scalar(&chunk[64..96])?,
+                })
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        Ok(Self { branches })
+    }
+}
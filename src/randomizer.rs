@@ -0,0 +1,151 @@
+//! Randomizers for `SpendAuth` keys.
+//!
+//! A [`Randomizer`] is a secret scalar that is added to a `SpendAuth` signing
+//! key, and correspondingly to its verification key, so that signatures can be
+//! made under a fresh, unlinkable key. Randomizers are either sampled from an
+//! RNG or derived from a shared seed and a public context, and are compared in
+//! constant time, zeroized when dropped and never printed.
+
+use core::fmt;
+
+use decaf377::Fr;
+use rand_core::{CryptoRng, RngCore};
+use subtle::{Choice, ConstantTimeEq};
+use zeroize::Zeroize;
+
+use crate::{Error, HStar, SigningKey, SpendAuth, VerificationKey};
+
+/// A scalar used to randomize `SpendAuth` keys.
+///
+/// Randomizing a signing key and its verification key with the same
+/// `Randomizer` produces a matching key pair, whose verification key cannot be
+/// linked to the original one without knowledge of the randomizer. The
+/// randomizer is zeroized when dropped.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "[u8; 32]", into = "[u8; 32]"))]
+pub struct Randomizer(Fr);
+
+impl Randomizer {
+    /// Generate a new randomizer from the supplied `rng`.
+    pub fn new<R: RngCore + CryptoRng>(mut rng: R) -> Self {
+        let mut bytes = [0u8; 64];
+        rng.fill_bytes(&mut bytes);
+        let randomizer = Self(Fr::from_le_bytes_mod_order(&bytes[..]));
+        bytes.zeroize();
+        randomizer
+    }
+
+    /// Deterministically derive a randomizer from a secret `seed` and a public
+    /// `context`, such as a transaction hash or a protocol transcript.
+    ///
+    /// The same seed and context always give the same randomizer, so both
+    /// parties holding the seed can agree on it without communicating.
+    pub fn derive(seed: &[u8], context: &[u8]) -> Self {
+        Self(
+            HStar::new(b"decaf377-rdsa-rz")
+                .update((seed.len() as u64).to_le_bytes())
+                .update(seed)
+                .update(context)
+                .finalize(),
+        )
+    }
+
+    /// Returns the byte encoding of the randomizer.
+    pub fn to_bytes(&self) -> [u8; 32] {
+        self.0.to_bytes()
+    }
+
+    /// Returns the randomizer as a scalar.
+    pub fn to_scalar(&self) -> Fr {
+        self.0
+    }
+
+    /// Randomize the signing key `sk`.
+    pub fn randomize_signing_key(&self, sk: &SigningKey<SpendAuth>) -> SigningKey<SpendAuth> {
+        sk.randomize(&self.0)
+    }
+
+    /// Randomize the verification key `vk`.
+    pub fn randomize_verification_key(
+        &self,
+        vk: &VerificationKey<SpendAuth>,
+    ) -> VerificationKey<SpendAuth> {
+        vk.randomize(&self.0)
+    }
+
+    /// Randomize the signing key `sk`, returning the randomized signing key
+    /// and its verification key.
+    pub fn randomize_key_pair(
+        &self,
+        sk: &SigningKey<SpendAuth>,
+    ) -> (SigningKey<SpendAuth>, VerificationKey<SpendAuth>) {
+        let rsk = self.randomize_signing_key(sk);
+        (rsk, rsk.pk)
+    }
+}
+
+impl Drop for Randomizer {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl From<Fr> for Randomizer {
+    fn from(scalar: Fr) -> Self {
+        Self(scalar)
+    }
+}
+
+impl TryFrom<[u8; 32]> for Randomizer {
+    type Error = Error;
+
+    fn try_from(bytes: [u8; 32]) -> Result<Self, Self::Error> {
+        Fr::from_bytes_checked(&bytes)
+            .map(Self)
+            .map_err(|_| Error::MalformedRandomizer)
+    }
+}
+
+impl TryFrom<&[u8]> for Randomizer {
+    type Error = Error;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        if bytes.len() == 32 {
+            let mut bytes32 = [0u8; 32];
+            bytes32.copy_from_slice(bytes);
+            bytes32.try_into()
+        } else {
+            Err(Error::WrongSliceLength {
+                expected: 32,
+                found: bytes.len(),
+            })
+        }
+    }
+}
+
+impl From<Randomizer> for [u8; 32] {
+    fn from(randomizer: Randomizer) -> [u8; 32] {
+        randomizer.to_bytes()
+    }
+}
+
+impl ConstantTimeEq for Randomizer {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.to_bytes().ct_eq(&other.to_bytes())
+    }
+}
+
+impl PartialEq for Randomizer {
+    fn eq(&self, other: &Self) -> bool {
+        self.ct_eq(other).into()
+    }
+}
+
+impl Eq for Randomizer {}
+
+impl fmt::Debug for Randomizer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Randomizer(<redacted>)")
+    }
+}
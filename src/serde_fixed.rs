@@ -0,0 +1,85 @@
+//! A fixed-length serde encoding for [`Signature`]s.
+//!
+//! By default, `Signature<D>` serializes as a `Vec<u8>`, so length-prefixed
+//! binary formats such as `bincode` emit an 8-byte length before each 64-byte
+//! signature. This module serializes the signature as a 64-element tuple
+//! instead, in the same way that [`VerificationKeyBytes`](crate::VerificationKeyBytes)
+//! is encoded as a `[u8; 32]`. It is opt-in, via `#[serde(with = ...)]`:
+//!
+//! ```
+//! use decaf377_rdsa::{Signature, SpendAuth};
+//!
+//! #[derive(serde::Serialize, serde::Deserialize)]
+//! struct Authorization {
+//!     #[serde(with = "decaf377_rdsa::serde_fixed")]
+//!     sig: Signature<SpendAuth>,
+//! }
+//! ```
+//!
+//! # Migrating existing data
+//!
+//! Self-describing formats (JSON, CBOR, etc.) encode both layouts as a
+//! sequence of 64 bytes, and [`deserialize`] also accepts formats that hand
+//! the signature over as a byte string, so data written with the default
+//! encoding can be read directly.
+//!
+//! Non-self-describing formats such as `bincode` do not record which layout
+//! was used, so the two cannot be told apart on read. Data in those formats
+//! should be migrated by decoding it with the default `Signature<D>`
+//! implementation and re-encoding it with this module.
+
+use core::{fmt, marker::PhantomData};
+
+use serde::{
+    de::{self, IgnoredAny, SeqAccess, Visitor},
+    ser::SerializeTuple,
+    Deserializer, Serializer,
+};
+
+use crate::{Domain, Signature};
+
+/// Serialize `sig` as a fixed-size tuple of 64 bytes.
+pub fn serialize<S: Serializer, D: Domain>(
+    sig: &Signature<D>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let mut tuple = serializer.serialize_tuple(64)?;
+    for byte in sig.as_ref() {
+        tuple.serialize_element(byte)?;
+    }
+    tuple.end()
+}
+
+/// Deserialize a signature encoded as 64 bytes, with no length prefix.
+pub fn deserialize<'de, De: Deserializer<'de>, D: Domain>(
+    deserializer: De,
+) -> Result<Signature<D>, De::Error> {
+    deserializer.deserialize_tuple(64, SignatureVisitor(PhantomData))
+}
+
+struct SignatureVisitor<D: Domain>(PhantomData<D>);
+
+impl<'de, D: Domain> Visitor<'de> for SignatureVisitor<D> {
+    type Value = Signature<D>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("64 bytes of signature data")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut bytes = [0u8; 64];
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            *byte = seq
+                .next_element()?
+                .ok_or_else(|| de::Error::invalid_length(i, &self))?;
+        }
+        if seq.next_element::<IgnoredAny>()?.is_some() {
+            return Err(de::Error::invalid_length(65, &self));
+        }
+        Ok(bytes.into())
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+        Signature::try_from(v).map_err(|_| E::invalid_length(v.len(), &self))
+    }
+}
@@ -0,0 +1,312 @@
+//! Shamir secret sharing of signing keys, for cold backup.
+//!
+//! A [`SigningKey`] can be split into `n` [`Share`]s, any `t` of which suffice
+//! to reconstruct it, while fewer reveal nothing about it. Splitting also
+//! produces Feldman [`Commitments`] to the sharing polynomial, which let each
+//! shareholder check their share against the public verification key without
+//! learning anything about the other shares.
+//!
+//! This is intended for backing up keys, not for threshold signing: the full
+//! signing key is present wherever shares are created or combined.
+//!
+//! A share is encoded in 35 bytes: the domain tag (`1` SpendAuth, `2`
+//! Binding), the threshold, the share index, and the share value. Commitments
+//! are encoded as the domain tag and the threshold, followed by the `t`
+//! commitments to the coefficients of the sharing polynomial, the first of
+//! which is the verification key.
+
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+use decaf377::{Element, Encoding, Fr};
+use rand_core::{CryptoRng, RngCore};
+use subtle::ConstantTimeEq;
+use zeroize::Zeroize;
+
+use crate::{Domain, Error, SigningKey, VerificationKey};
+
+/// The length of an encoded [`Share`].
+pub const SHARE_LEN: usize = 35;
+
+/// One share of a signing key.
+#[derive(Clone)]
+pub struct Share<D: Domain> {
+    threshold: u8,
+    index: u8,
+    value: Fr,
+    _marker: PhantomData<D>,
+}
+
+impl<D: Domain> Share<D> {
+    /// Returns the index of this share, which is between 1 and the number of
+    /// shares.
+    pub fn index(&self) -> u8 {
+        self.index
+    }
+
+    /// Returns the number of shares needed to reconstruct the signing key.
+    pub fn threshold(&self) -> u8 {
+        self.threshold
+    }
+
+    /// Returns the byte encoding of this share.
+    pub fn to_bytes(&self) -> [u8; SHARE_LEN] {
+        let mut bytes = [0u8; SHARE_LEN];
+        bytes[0] = D::TAG;
+        bytes[1] = self.threshold;
+        bytes[2] = self.index;
+        bytes[3..].copy_from_slice(&self.value.to_bytes());
+        bytes
+    }
+
+    /// Check this share against the `commitments` published when the key was
+    /// split, and the commitments against the verification key `vk` of the
+    /// shared signing key.
+    pub fn verify(
+        &self,
+        commitments: &Commitments<D>,
+        vk: &VerificationKey<D>,
+    ) -> Result<(), Error> {
+        if self.threshold as usize != commitments.points.len()
+            || commitments.points[0].vartime_compress().0 != vk.to_bytes()
+        {
+            return Err(Error::InvalidShare);
+        }
+
+        // Evaluate the committed polynomial at the share index, in the
+        // exponent, using Horner's rule.
+        let x = Fr::from(self.index as u64);
+        let expected = commitments
+            .points
+            .iter()
+            .rev()
+            .fold(Element::IDENTITY, |acc, point| acc * x + point);
+
+        if crate::ct::basepoint_mul::<D>(&self.value) == expected.vartime_compress().0 {
+            Ok(())
+        } else {
+            Err(Error::InvalidShare)
+        }
+    }
+}
+
+impl<D: Domain> Drop for Share<D> {
+    fn drop(&mut self) {
+        self.value.zeroize();
+    }
+}
+
+impl<D: Domain> TryFrom<&[u8]> for Share<D> {
+    type Error = Error;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        if bytes.len() != SHARE_LEN {
+            return Err(Error::WrongSliceLength {
+                expected: SHARE_LEN,
+                found: bytes.len(),
+            });
+        }
+        if bytes[0] != D::TAG {
+            return Err(Error::WrongDomain);
+        }
+        let (threshold, index) = (bytes[1], bytes[2]);
+        if threshold == 0 || index == 0 {
+            return Err(Error::InvalidShare);
+        }
+        let value = Fr::from_bytes_checked(bytes[3..].try_into().expect("32 bytes"))
+            .map_err(|_| Error::InvalidShare)?;
+        Ok(Share {
+            threshold,
+            index,
+            value,
+            _marker: PhantomData,
+        })
+    }
+}
+
+/// Feldman commitments to the polynomial used to split a signing key.
+#[derive(Clone, Debug)]
+pub struct Commitments<D: Domain> {
+    points: Vec<Element>,
+    _marker: PhantomData<D>,
+}
+
+impl<D: Domain> Commitments<D> {
+    /// Returns the number of shares needed to reconstruct the signing key.
+    pub fn threshold(&self) -> u8 {
+        self.points.len() as u8
+    }
+
+    /// Returns the verification key of the shared signing key.
+    pub fn verification_key(&self) -> VerificationKey<D> {
+        VerificationKey::try_from(self.points[0].vartime_compress().0)
+            .expect("commitments are valid encodings")
+    }
+
+    /// Returns the byte encoding of these commitments.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(2 + 32 * self.points.len());
+        bytes.push(D::TAG);
+        bytes.push(self.threshold());
+        for point in &self.points {
+            bytes.extend_from_slice(&point.vartime_compress().0);
+        }
+        bytes
+    }
+}
+
+impl<D: Domain> TryFrom<&[u8]> for Commitments<D> {
+    type Error = Error;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        if bytes.len() < 2 {
+            return Err(Error::InvalidShare);
+        }
+        if bytes[0] != D::TAG {
+            return Err(Error::WrongDomain);
+        }
+        let threshold = bytes[1] as usize;
+        if threshold == 0 {
+            return Err(Error::InvalidShare);
+        }
+        let expected = 2 + 32 * threshold;
+        if bytes.len() != expected {
+            return Err(Error::WrongSliceLength {
+                expected,
+                found: bytes.len(),
+            });
+        }
+        let points = bytes[2..]
+            .chunks_exact(32)
+            .map(|chunk| {
+                Encoding(chunk.try_into().expect("32 bytes"))
+                    .vartime_decompress()
+                    .map_err(|_| Error::InvalidShare)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Commitments {
+            points,
+            _marker: PhantomData,
+        })
+    }
+}
+
+impl<D: Domain> SigningKey<D> {
+    /// Split this signing key into `n` shares, any `threshold` of which can
+    /// reconstruct it, using the supplied `rng`.
+    ///
+    /// Returns [`Error::InvalidThreshold`] unless `1 <= threshold <= n`.
+    pub fn split<R: RngCore + CryptoRng>(
+        &self,
+        mut rng: R,
+        threshold: u8,
+        n: u8,
+    ) -> Result<(Vec<Share<D>>, Commitments<D>), Error> {
+        if threshold == 0 || threshold > n {
+            return Err(Error::InvalidThreshold);
+        }
+
+        let mut coefficients = Vec::with_capacity(threshold as usize);
+        coefficients.push(self.sk);
+        for _ in 1..threshold {
+            let mut bytes = [0u8; 64];
+            rng.fill_bytes(&mut bytes);
+            coefficients.push(Fr::from_le_bytes_mod_order(&bytes[..]));
+            bytes.zeroize();
+        }
+
+        let shares = (1..=n)
+            .map(|index| {
+                let x = Fr::from(index as u64);
+                let value = coefficients
+                    .iter()
+                    .rev()
+                    .fold(Fr::from(0u64), |acc, a| acc * x + a);
+                Share {
+                    threshold,
+                    index,
+                    value,
+                    _marker: PhantomData,
+                }
+            })
+            .collect();
+
+        let points = coefficients
+            .iter()
+            .map(|a| {
+                Encoding(crate::ct::basepoint_mul::<D>(a))
+                    .vartime_decompress()
+                    .expect("commitments are valid encodings")
+            })
+            .collect();
+
+        coefficients.iter_mut().for_each(Zeroize::zeroize);
+
+        Ok((
+            shares,
+            Commitments {
+                points,
+                _marker: PhantomData,
+            },
+        ))
+    }
+
+    /// Reconstruct a signing key from `shares`, checking that it matches the
+    /// verification key `vk`.
+    ///
+    /// At least as many shares as the threshold are required, and any shares
+    /// beyond the threshold must lie on the polynomial interpolated from the
+    /// others. Returns [`Error::InvalidShare`] if the shares are inconsistent or
+    /// insufficient, or do not reconstruct the signing key for `vk`.
+    pub fn reconstruct(shares: &[Share<D>], vk: &VerificationKey<D>) -> Result<Self, Error> {
+        let threshold = match shares.first() {
+            Some(share) => share.threshold,
+            None => return Err(Error::InvalidShare),
+        };
+        if shares.len() < threshold as usize || shares.iter().any(|s| s.threshold != threshold) {
+            return Err(Error::InvalidShare);
+        }
+        for (i, share) in shares.iter().enumerate() {
+            if shares[..i].iter().any(|other| other.index == share.index) {
+                return Err(Error::InvalidShare);
+            }
+        }
+
+        let (base, extra) = shares.split_at(threshold as usize);
+        for share in extra {
+            let mut value = interpolate(base, Fr::from(share.index as u64));
+            let consistent = value.to_bytes().ct_eq(&share.value.to_bytes());
+            value.zeroize();
+            if !bool::from(consistent) {
+                return Err(Error::InvalidShare);
+            }
+        }
+
+        let mut sk = interpolate(base, Fr::from(0u64));
+        let key = SigningKey::new_from_field(sk);
+        sk.zeroize();
+        if key.pk != *vk {
+            return Err(Error::InvalidShare);
+        }
+        Ok(key)
+    }
+}
+
+/// Evaluates the sharing polynomial through `shares` at `x`, using Lagrange
+/// interpolation. The share indices must be distinct.
+fn interpolate<D: Domain>(shares: &[Share<D>], x: Fr) -> Fr {
+    let mut value = Fr::from(0u64);
+    for share in shares {
+        let x_i = Fr::from(share.index as u64);
+        let mut numerator = Fr::from(1u64);
+        let mut denominator = Fr::from(1u64);
+        for other in shares.iter().filter(|other| other.index != share.index) {
+            let x_j = Fr::from(other.index as u64);
+            numerator *= x - x_j;
+            denominator *= x_i - x_j;
+        }
+        let lagrange = numerator * denominator.inverse().expect("indices are distinct");
+        value += lagrange * share.value;
+    }
+    value
+}
@@ -84,10 +84,11 @@ impl<D: Domain> cmp::PartialEq for Signature<D> {
 
 impl<D: Domain> cmp::Eq for Signature<D> {}
 
-#[cfg(feature = "std")]
-mod std_only {
+#[cfg(feature = "alloc")]
+mod alloc_only {
     use super::*;
-    use std::fmt;
+    use alloc::vec::Vec;
+    use core::fmt;
 
     use crate::{Binding, Signature, SpendAuth};
 
@@ -108,7 +109,7 @@ mod std_only {
     impl fmt::Debug for Signature<Binding> {
         fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
             f.debug_tuple("Signature<Binding>")
-                .field(&hex::encode(&<[u8; 64]>::from(*self)))
+                .field(&hex::encode(<[u8; 64]>::from(*self)))
                 .finish()
         }
     }
@@ -116,11 +117,8 @@ mod std_only {
     impl fmt::Debug for Signature<SpendAuth> {
         fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
             f.debug_tuple("Signature<SpendAuth>")
-                .field(&hex::encode(&<[u8; 64]>::from(*self)))
+                .field(&hex::encode(<[u8; 64]>::from(*self)))
                 .finish()
         }
     }
 }
-
-#[cfg(feature = "std")]
-pub use std_only::*;
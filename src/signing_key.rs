@@ -10,19 +10,19 @@ use crate::{Domain, Error, Signature, SpendAuth, VerificationKey};
 #[cfg_attr(feature = "serde", serde(into = "SerdeHelper"))]
 #[cfg_attr(feature = "serde", serde(bound = "D: Domain"))]
 pub struct SigningKey<D: Domain> {
-    sk: Fr,
-    pk: VerificationKey<D>,
+    pub(crate) sk: Fr,
+    pub(crate) pk: VerificationKey<D>,
 }
 
 impl<'a, D: Domain> From<&'a SigningKey<D>> for VerificationKey<D> {
     fn from(sk: &'a SigningKey<D>) -> VerificationKey<D> {
-        sk.pk.clone()
+        sk.pk
     }
 }
 
-impl<'a, D: Domain> From<SigningKey<D>> for VerificationKey<D> {
+impl<D: Domain> From<SigningKey<D>> for VerificationKey<D> {
     fn from(sk: SigningKey<D>) -> VerificationKey<D> {
-        sk.pk.clone()
+        sk.pk
     }
 }
 
@@ -137,6 +137,41 @@ impl<D: Domain> SigningKey<D> {
         self.sign_inner(&bonus_randomness, msg)
     }
 
+    /// Create a signature for domain `D` on `msg` using this `SigningKey`,
+    /// verifying it before it is returned.
+    ///
+    /// A fault during signing, such as a glitch flipping a bit in the scalar
+    /// arithmetic, can produce an invalid signature from which the signing key
+    /// can be recovered. This method checks the signature against the
+    /// verification key, and returns [`Error::FaultDetected`] rather than
+    /// releasing a faulty signature. This costs one verification on top of
+    /// signing.
+    pub fn sign_hardened<R: RngCore + CryptoRng>(
+        &self,
+        mut rng: R,
+        msg: &[u8],
+    ) -> Result<Signature<D>, Error> {
+        let mut bonus_randomness = [0u8; 48];
+        rng.fill_bytes(&mut bonus_randomness);
+        self.check_signature(msg, self.sign_inner(&bonus_randomness, msg))
+    }
+
+    /// Create a deterministic signature for domain `D` on `msg` using this
+    /// `SigningKey`, verifying it before it is returned.
+    ///
+    /// See [`SigningKey::sign_hardened`] and [`SigningKey::sign_deterministic`].
+    pub fn sign_deterministic_hardened(&self, msg: &[u8]) -> Result<Signature<D>, Error> {
+        let bonus_randomness = [0u8; 48];
+        self.check_signature(msg, self.sign_inner(&bonus_randomness, msg))
+    }
+
+    fn check_signature(&self, msg: &[u8], signature: Signature<D>) -> Result<Signature<D>, Error> {
+        self.pk
+            .verify(msg, &signature)
+            .map_err(|_| Error::FaultDetected)?;
+        Ok(signature)
+    }
+
     fn sign_inner(&self, bonus_randomness: &[u8; 48], msg: &[u8]) -> Signature<D> {
         use crate::HStar;
 
@@ -154,7 +189,7 @@ impl<D: Domain> SigningKey<D> {
             .update(msg)
             .finalize();
 
-        let r_bytes = (&D::basepoint() * &nonce).vartime_compress().0;
+        let r_bytes = crate::ct::basepoint_mul::<D>(&nonce);
 
         let c = HStar::default()
             .update(&r_bytes[..])
@@ -168,17 +203,17 @@ impl<D: Domain> SigningKey<D> {
     }
 }
 
-#[cfg(feature = "std")]
-mod std_only {
+#[cfg(feature = "alloc")]
+mod alloc_only {
     use super::*;
-    use std::fmt;
+    use core::fmt;
 
     use crate::Binding;
 
     impl fmt::Debug for SigningKey<Binding> {
         fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
             f.debug_tuple("SigningKey<Binding>")
-                .field(&hex::encode(&<[u8; 32]>::from(*self)))
+                .field(&hex::encode(<[u8; 32]>::from(*self)))
                 .finish()
         }
     }
@@ -186,11 +221,28 @@ mod std_only {
     impl fmt::Debug for SigningKey<SpendAuth> {
         fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
             f.debug_tuple("SigningKey<SpendAuth>")
-                .field(&hex::encode(&<[u8; 32]>::from(*self)))
+                .field(&hex::encode(<[u8; 32]>::from(*self)))
                 .finish()
         }
     }
 }
 
-#[cfg(feature = "std")]
-pub use std_only::*;
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_signature_detects_faults() {
+        let sk = SigningKey::<SpendAuth>::new(rand::thread_rng());
+        let msg = b"fault";
+        let signature = sk.sign_inner(&[0u8; 48], msg);
+        assert!(sk.check_signature(msg, signature).is_ok());
+
+        let mut bytes = <[u8; 64]>::from(signature);
+        bytes[40] ^= 1;
+        assert_eq!(
+            sk.check_signature(msg, Signature::from(bytes)),
+            Err(Error::FaultDetected)
+        );
+    }
+}
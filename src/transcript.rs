@@ -0,0 +1,74 @@
+//! Signing and verification with Merlin transcripts.
+//!
+//! These methods derive the signature challenge from a caller-supplied
+//! [`Transcript`] rather than from [`HStar`](crate::HStar), so that a signature
+//! can be bound to a larger protocol transcript. The message being signed is
+//! whatever the caller has appended to the transcript beforehand.
+//!
+//! Signing and verification append the following to the transcript, in order:
+//!
+//! 1. the domain tag, as a single byte, with the label `decaf377-rdsa-domain`;
+//! 2. the verification key, with the label `vk`;
+//! 3. the signature's `R` value, with the label `R`;
+//!
+//! and then take 64 challenge bytes with the label `c`, which are reduced
+//! modulo the group order to obtain the challenge scalar. The nonce is derived
+//! from the transcript, the signing key and the supplied RNG using Merlin's
+//! transcript RNG.
+
+use decaf377::Fr;
+use merlin::Transcript;
+use rand_core::{CryptoRng, RngCore};
+
+use crate::{Domain, Error, Signature, SigningKey, VerificationKey, VerificationKeyBytes};
+
+fn append_key<D: Domain>(transcript: &mut Transcript, vk_bytes: &VerificationKeyBytes<D>) {
+    transcript.append_message(b"decaf377-rdsa-domain", &[D::TAG]);
+    transcript.append_message(b"vk", &vk_bytes.bytes);
+}
+
+fn challenge(transcript: &mut Transcript, r_bytes: &[u8; 32]) -> Fr {
+    transcript.append_message(b"R", r_bytes);
+    let mut bytes = [0u8; 64];
+    transcript.challenge_bytes(b"c", &mut bytes);
+    Fr::from_le_bytes_mod_order(&bytes[..])
+}
+
+impl<D: Domain> SigningKey<D> {
+    /// Create a signature for domain `D`, binding it to `transcript`.
+    pub fn sign_with_transcript<R: RngCore + CryptoRng>(
+        &self,
+        mut rng: R,
+        transcript: &mut Transcript,
+    ) -> Signature<D> {
+        append_key(transcript, &self.pk.bytes);
+
+        let mut nonce_bytes = [0u8; 64];
+        transcript
+            .build_rng()
+            .rekey_with_witness_bytes(b"sk", &self.sk.to_bytes())
+            .finalize(&mut rng)
+            .fill_bytes(&mut nonce_bytes);
+        let nonce = Fr::from_le_bytes_mod_order(&nonce_bytes[..]);
+
+        let r_bytes = crate::ct::basepoint_mul::<D>(&nonce);
+        let c = challenge(transcript, &r_bytes);
+        let s_bytes = (nonce + (c * self.sk)).to_bytes();
+
+        Signature::from_parts(r_bytes, s_bytes)
+    }
+}
+
+impl<D: Domain> VerificationKey<D> {
+    /// Verify a purported `signature` made by this verification key, bound to
+    /// `transcript`.
+    pub fn verify_with_transcript(
+        &self,
+        transcript: &mut Transcript,
+        signature: &Signature<D>,
+    ) -> Result<(), Error> {
+        append_key(transcript, &self.bytes);
+        let c = challenge(transcript, &signature.r_bytes());
+        self.verify_prehashed(signature, c)
+    }
+}
@@ -6,7 +6,7 @@ use core::{
 
 use decaf377::Fr;
 
-use crate::{domain::Sealed, Domain, Error, Signature, SpendAuth};
+use crate::{Domain, Error, HStar, Signature, SpendAuth};
 
 /// A refinement type for `[u8; 32]` indicating that the bytes represent
 /// an encoding of a `decaf377-rdsa` verification key.
@@ -42,6 +42,17 @@ impl<D: Domain> From<VerificationKeyBytes<D>> for [u8; 32] {
     }
 }
 
+impl<D: Domain> VerificationKeyBytes<D> {
+    /// Compute the challenge scalar binding `signature` to this key and `msg`.
+    pub(crate) fn challenge(&self, msg: &[u8], signature: &Signature<D>) -> Fr {
+        HStar::default()
+            .update(&signature.r_bytes()[..])
+            .update(&self.bytes[..])
+            .update(msg)
+            .finalize()
+    }
+}
+
 impl<D: Domain> Hash for VerificationKeyBytes<D> {
     fn hash<H: Hasher>(&self, state: &mut H) {
         self.bytes.hash(state);
@@ -51,7 +62,7 @@ impl<D: Domain> Hash for VerificationKeyBytes<D> {
 
 impl<D: Domain> PartialOrd for VerificationKeyBytes<D> {
     fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
-        self.bytes.partial_cmp(&other.bytes)
+        Some(self.cmp(other))
     }
 }
 
@@ -84,7 +95,7 @@ impl<D: Domain> Hash for VerificationKey<D> {
 
 impl<D: Domain> PartialOrd for VerificationKey<D> {
     fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
-        self.bytes.partial_cmp(&other.bytes)
+        Some(self.cmp(other))
     }
 }
 
@@ -125,15 +136,42 @@ impl<D: Domain> TryFrom<VerificationKeyBytes<D>> for VerificationKey<D> {
     type Error = Error;
 
     fn try_from(bytes: VerificationKeyBytes<D>) -> Result<Self, Self::Error> {
-        // Note: the identity element is allowed as a verification key.
-        let point = decaf377::Encoding(bytes.bytes)
-            .vartime_decompress()
-            .map_err(|_| Error::MalformedVerificationKey)?;
+        #[cfg(feature = "key_cache")]
+        let point = crate::key_cache::decompress_cached::<D>(bytes.bytes, decompress)?;
+        #[cfg(not(feature = "key_cache"))]
+        let point = decompress(bytes.bytes)?;
 
         Ok(VerificationKey { point, bytes })
     }
 }
 
+/// Decompress `encodings` with `decompress`, calling it only once for each
+/// distinct encoding.
+#[cfg(feature = "alloc")]
+fn batch_decompress(
+    encodings: impl Iterator<Item = [u8; 32]>,
+    decompress: impl Fn([u8; 32]) -> Result<decaf377::Element, Error>,
+) -> Result<alloc::vec::Vec<decaf377::Element>, Error> {
+    use alloc::collections::btree_map::{BTreeMap, Entry};
+
+    let mut points = BTreeMap::new();
+    encodings
+        .map(|bytes| match points.entry(bytes) {
+            Entry::Occupied(entry) => Ok(*entry.get()),
+            Entry::Vacant(entry) => Ok(*entry.insert(decompress(bytes)?)),
+        })
+        .collect()
+}
+
+/// Decompress the verification key encoded by `bytes`, without consulting
+/// the decompression cache.
+fn decompress(bytes: [u8; 32]) -> Result<decaf377::Element, Error> {
+    // Note: the identity element is allowed as a verification key.
+    decaf377::Encoding(bytes)
+        .vartime_decompress()
+        .map_err(|_| Error::MalformedVerificationKey)
+}
+
 impl<D: Domain> TryFrom<[u8; 32]> for VerificationKey<D> {
     type Error = Error;
 
@@ -148,7 +186,7 @@ impl<D: Domain> TryFrom<&[u8]> for VerificationKeyBytes<D> {
     fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
         if bytes.len() == 32 {
             let mut bytes32 = [0u8; 32];
-            bytes32.copy_from_slice(&bytes);
+            bytes32.copy_from_slice(bytes);
             Ok(bytes32.into())
         } else {
             Err(Error::WrongSliceLength {
@@ -178,37 +216,81 @@ impl VerificationKey<SpendAuth> {
     ///
     /// Randomization is only supported for `SpendAuth` keys.
     pub fn randomize(&self, randomizer: &Fr) -> VerificationKey<SpendAuth> {
-        let point = self.point + (SpendAuth::basepoint() * randomizer);
-        let bytes = VerificationKeyBytes {
-            bytes: point.vartime_compress().into(),
-            _marker: PhantomData,
-        };
-        VerificationKey { bytes, point }
+        // Bypass the decompression cache, since randomized keys are used once.
+        let bytes = crate::ct::add_basepoint_mul::<SpendAuth>(&self.bytes.bytes, randomizer);
+        let point = decompress(bytes).expect("randomized keys are valid encodings");
+        VerificationKey {
+            point,
+            bytes: bytes.into(),
+        }
     }
 }
 
 impl<D: Domain> VerificationKey<D> {
     pub(crate) fn from(s: &Fr) -> VerificationKey<D> {
-        let point = &D::basepoint() * s;
-        let bytes = VerificationKeyBytes {
-            bytes: point.vartime_compress().into(),
-            _marker: PhantomData,
-        };
-        VerificationKey { bytes, point }
+        // Bypass the decompression cache, whose hits and misses would reveal
+        // whether this key has been derived before.
+        let bytes = crate::ct::basepoint_mul::<D>(s);
+        let point = decompress(bytes).expect("derived keys are valid encodings");
+        VerificationKey {
+            point,
+            bytes: bytes.into(),
+        }
     }
 
     /// Verify a purported `signature` over `msg` made by this verification key.
     // This is similar to impl signature::Verifier but without boxed errors
     pub fn verify(&self, msg: &[u8], signature: &Signature<D>) -> Result<(), Error> {
-        use crate::HStar;
-        let c = HStar::default()
-            .update(&signature.r_bytes()[..])
-            .update(&self.bytes.bytes[..]) // XXX ugly
-            .update(msg)
-            .finalize();
+        let c = self.bytes.challenge(msg, signature);
         self.verify_prehashed(signature, c)
     }
 
+    /// Verify a purported `signature` over `msg` made by this verification key,
+    /// consulting and populating `cache`.
+    #[cfg(feature = "cache")]
+    pub fn verify_cached(
+        &self,
+        msg: &[u8],
+        signature: &Signature<D>,
+        cache: &crate::cache::VerificationCache,
+    ) -> Result<(), Error> {
+        let c = self.bytes.challenge(msg, signature);
+        let key = crate::cache::CacheKey::new(&self.bytes, signature, &c);
+        if cache.contains(&key) {
+            return Ok(());
+        }
+        self.verify_prehashed(signature, c)?;
+        cache.insert(key);
+        Ok(())
+    }
+
+    /// Decompress many verification keys at once, returning
+    /// [`Error::MalformedVerificationKey`] if any of them is invalid.
+    ///
+    /// Each distinct encoding is decompressed only once, which makes this
+    /// cheaper than decompressing `keys` one by one when they contain
+    /// repeated keys. Unlike inversions, the square root that dominates the
+    /// cost of decompression cannot be shared between distinct points with
+    /// Montgomery's trick, so distinct keys still cost one decompression each,
+    /// unless they are found in the decompression cache of the `key_cache`
+    /// feature.
+    #[cfg(feature = "alloc")]
+    pub fn batch_try_from(
+        keys: &[VerificationKeyBytes<D>],
+    ) -> Result<alloc::vec::Vec<Self>, Error> {
+        let points = batch_decompress(keys.iter().map(|bytes| bytes.bytes), |bytes| {
+            VerificationKey::<D>::try_from(bytes).map(|vk| vk.point)
+        })?;
+        Ok(points
+            .into_iter()
+            .zip(keys)
+            .map(|(point, bytes)| VerificationKey {
+                point,
+                bytes: *bytes,
+            })
+            .collect())
+    }
+
     /// Convenience method for identity checks.
     pub fn is_identity(&self) -> bool {
         self.point == decaf377::Element::IDENTITY
@@ -224,6 +306,13 @@ impl<D: Domain> VerificationKey<D> {
         let s =
             Fr::from_bytes_checked(&signature.s_bytes()).map_err(|_| Error::InvalidSignature)?;
 
+        self.verify_parsed(R, s, c)
+    }
+
+    /// Verify a purported signature, given as its decompressed `R` value and
+    /// parsed `s` value, with a prehashed challenge.
+    #[allow(non_snake_case)]
+    pub(crate) fn verify_parsed(&self, R: decaf377::Element, s: Fr, c: Fr) -> Result<(), Error> {
         // XXX rewrite as normal double scalar mul
         // Verify check is h * ( - s * B + R  + c * A) == 0
         //                 h * ( s * B - c * A - R) == 0
@@ -254,44 +343,41 @@ impl<D: Domain> PartialEq for VerificationKeyBytes<D> {
 impl<D: Domain> Eq for VerificationKey<D> {}
 impl<D: Domain> Eq for VerificationKeyBytes<D> {}
 
-#[cfg(feature = "std")]
-mod std_only {
+#[cfg(feature = "alloc")]
+mod alloc_only {
     use super::*;
 
     use crate::Binding;
 
-    impl std::fmt::Debug for VerificationKey<Binding> {
-        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    impl core::fmt::Debug for VerificationKey<Binding> {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
             f.debug_tuple("VerificationKey<Binding>")
-                .field(&hex::encode(&<[u8; 32]>::from(*self)))
+                .field(&hex::encode(<[u8; 32]>::from(*self)))
                 .finish()
         }
     }
 
-    impl std::fmt::Debug for VerificationKey<SpendAuth> {
-        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    impl core::fmt::Debug for VerificationKey<SpendAuth> {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
             f.debug_tuple("VerificationKey<SpendAuth>")
-                .field(&hex::encode(&<[u8; 32]>::from(*self)))
+                .field(&hex::encode(<[u8; 32]>::from(*self)))
                 .finish()
         }
     }
 
-    impl std::fmt::Debug for VerificationKeyBytes<Binding> {
-        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    impl core::fmt::Debug for VerificationKeyBytes<Binding> {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
             f.debug_tuple("VerificationKeyBytes<Binding>")
-                .field(&hex::encode(&<[u8; 32]>::from(*self)))
+                .field(&hex::encode(<[u8; 32]>::from(*self)))
                 .finish()
         }
     }
 
-    impl std::fmt::Debug for VerificationKeyBytes<SpendAuth> {
-        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    impl core::fmt::Debug for VerificationKeyBytes<SpendAuth> {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
             f.debug_tuple("VerificationKeyBytes<SpendAuth>")
-                .field(&hex::encode(&<[u8; 32]>::from(*self)))
+                .field(&hex::encode(<[u8; 32]>::from(*self)))
                 .finish()
         }
     }
 }
-
-#[cfg(feature = "std")]
-pub use std_only::*;
@@ -97,3 +97,410 @@ fn bad_batch_verify() {
         }
     }
 }
+
+#[test]
+fn array_batch_verify() {
+    let mut rng = thread_rng();
+    let mut batch = batch::ArrayVerifier::<32>::new();
+    for i in 0..32 {
+        let item: batch::Item = match i % 2 {
+            0 => {
+                let sk = SigningKey::<SpendAuth>::new(&mut rng);
+                let vk = VerificationKey::from(&sk);
+                let msg = b"BatchVerifyTest";
+                let sig = sk.sign(&mut rng, &msg[..]);
+                (vk.into(), sig, msg).into()
+            }
+            1 => {
+                let sk = SigningKey::<Binding>::new(&mut rng);
+                let vk = VerificationKey::from(&sk);
+                let msg = b"BatchVerifyTest";
+                let sig = sk.sign(&mut rng, &msg[..]);
+                (vk.into(), sig, msg).into()
+            }
+            _ => unreachable!(),
+        };
+        assert!(batch.queue(item).is_ok());
+    }
+    assert!(batch.is_full());
+    assert!(batch.verify(rng).is_ok());
+}
+
+#[test]
+fn array_batch_rejects_overflow() {
+    let mut rng = thread_rng();
+    let mut batch = batch::ArrayVerifier::<1>::new();
+    let sk = SigningKey::<SpendAuth>::new(&mut rng);
+    let vk = VerificationKey::from(&sk);
+    let msg = b"BatchVerifyTest";
+    let sig = sk.sign(&mut rng, &msg[..]);
+    assert!(batch.queue((vk.into(), sig, msg)).is_ok());
+    assert!(batch.queue((vk.into(), sig, msg)).is_err());
+    assert_eq!(batch.len(), 1);
+    assert!(batch.verify(rng).is_ok());
+}
+
+#[test]
+fn bad_array_batch_verify() {
+    let mut rng = thread_rng();
+    let mut batch = batch::ArrayVerifier::<8>::new();
+    for i in 0..8 {
+        let sk = SigningKey::<Binding>::new(&mut rng);
+        let vk = VerificationKey::from(&sk);
+        let msg = b"BatchVerifyTest";
+        let sig = if i != 3 {
+            sk.sign(&mut rng, &msg[..])
+        } else {
+            sk.sign(&mut rng, b"bad")
+        };
+        assert!(batch.queue((vk.into(), sig, msg)).is_ok());
+    }
+    assert!(batch.verify(rng).is_err());
+}
+
+#[test]
+fn deterministic_batch_verify() {
+    let mut rng = thread_rng();
+    let mut batch = batch::Verifier::new();
+    let mut bad_batch = batch::Verifier::new();
+    let mut array_batch = batch::ArrayVerifier::<16>::new();
+    for i in 0..16 {
+        let msg = b"BatchVerifyTest";
+        let (item, bad_item): (batch::Item, batch::Item) = match i % 2 {
+            0 => {
+                let sk = SigningKey::<SpendAuth>::new(&mut rng);
+                let vk = VerificationKey::from(&sk);
+                let sig = sk.sign(&mut rng, &msg[..]);
+                (
+                    (vk.into(), sig, msg).into(),
+                    (vk.into(), sig, b"bad").into(),
+                )
+            }
+            1 => {
+                let sk = SigningKey::<Binding>::new(&mut rng);
+                let vk = VerificationKey::from(&sk);
+                let sig = sk.sign(&mut rng, &msg[..]);
+                (
+                    (vk.into(), sig, msg).into(),
+                    (vk.into(), sig, b"bad").into(),
+                )
+            }
+            _ => unreachable!(),
+        };
+        assert!(array_batch.queue(item.clone()).is_ok());
+        bad_batch.queue(if i == 5 { bad_item } else { item.clone() });
+        batch.queue(item);
+    }
+    assert!(batch.verify_deterministic().is_ok());
+    assert!(array_batch.verify_deterministic().is_ok());
+    assert!(bad_batch.verify_deterministic().is_err());
+}
+
+#[test]
+fn same_key_batch_verify() {
+    let mut rng = thread_rng();
+    let spendauth = SigningKey::<SpendAuth>::new(&mut rng);
+    let binding = SigningKey::<Binding>::new(&mut rng);
+    let msg = b"BatchVerifyTest";
+
+    let mut batch = batch::Verifier::new();
+    let mut bad_batch = batch::Verifier::new();
+    for i in 0..16 {
+        let sig = spendauth.sign(&mut rng, &msg[..]);
+        let vk = VerificationKey::from(&spendauth);
+        batch.queue((vk.into(), sig, msg));
+        if i == 7 {
+            bad_batch.queue((vk.into(), sig, b"bad"));
+        } else {
+            bad_batch.queue((vk.into(), sig, msg));
+        }
+
+        let sig = binding.sign(&mut rng, &msg[..]);
+        let vk = VerificationKey::from(&binding);
+        batch.queue((vk.into(), sig, msg));
+        bad_batch.queue((vk.into(), sig, msg));
+    }
+    assert!(batch.verify(&mut rng).is_ok());
+    assert!(bad_batch.verify(&mut rng).is_err());
+}
+
+#[test]
+fn batch_decompress_verification_keys() {
+    let mut rng = thread_rng();
+    let keys: Vec<VerificationKeyBytes<SpendAuth>> = (0..8)
+        .map(|_| VerificationKey::from(&SigningKey::<SpendAuth>::new(&mut rng)).into())
+        .collect();
+    let mut with_repeats = keys.clone();
+    with_repeats.extend_from_slice(&keys[..4]);
+
+    let vks = VerificationKey::batch_try_from(&with_repeats).unwrap();
+    assert_eq!(vks.len(), with_repeats.len());
+    for (vk, bytes) in vks.iter().zip(&with_repeats) {
+        assert_eq!(*vk, VerificationKey::try_from(*bytes).unwrap());
+    }
+
+    with_repeats.push([0xff; 32].into());
+    assert_eq!(
+        VerificationKey::batch_try_from(&with_repeats),
+        Err(Error::MalformedVerificationKey)
+    );
+}
+
+#[test]
+fn batch_verify_with_decompressed_keys() {
+    let mut rng = thread_rng();
+    let msg = b"BatchVerifyTest";
+    let mut batch = batch::Verifier::new();
+    let mut items = Vec::new();
+    for i in 0..8 {
+        let spendauth = SigningKey::<SpendAuth>::new(&mut rng);
+        let sig = spendauth.sign(&mut rng, &msg[..]);
+        let item = batch::Item::from_verification_key(spendauth.into(), sig, msg);
+        items.push(item.clone());
+        batch.queue(item);
+
+        let binding = SigningKey::<Binding>::new(&mut rng);
+        let sig = if i == 3 {
+            binding.sign(&mut rng, b"bad")
+        } else {
+            binding.sign(&mut rng, &msg[..])
+        };
+        let item = batch::Item::from_verification_key(binding.into(), sig, msg);
+        items.push(item.clone());
+        batch.queue(item);
+    }
+    assert!(batch.verify(&mut rng).is_err());
+    for (i, item) in items.into_iter().enumerate() {
+        assert_eq!(item.verify_single().is_ok(), i != 7);
+    }
+}
+
+#[test]
+fn batch_verify_with_parsed_signatures() {
+    let mut rng = thread_rng();
+    let msg = b"BatchVerifyTest";
+    let mut batch = batch::Verifier::new();
+    let mut items = Vec::new();
+    for i in 0..8 {
+        let spendauth = SigningKey::<SpendAuth>::new(&mut rng);
+        let sig = spendauth.sign(&mut rng, &msg[..]).try_into().unwrap();
+        let item = batch::Item::from_parsed(spendauth.into(), sig, msg);
+        items.push(item.clone());
+        batch.queue(item);
+
+        let binding = SigningKey::<Binding>::new(&mut rng);
+        let sig = if i == 3 {
+            binding.sign(&mut rng, b"bad")
+        } else {
+            binding.sign(&mut rng, &msg[..])
+        };
+        let sig = batch::ParsedSignature::try_from(sig).unwrap();
+        let item = batch::Item::from_parsed(binding.into(), sig, msg);
+        items.push(item.clone());
+        batch.queue(item);
+    }
+    assert!(batch.verify(&mut rng).is_err());
+    for (i, item) in items.into_iter().enumerate() {
+        assert_eq!(item.verify_single().is_ok(), i != 7);
+    }
+
+    assert!(matches!(
+        batch::ParsedSignature::try_from(Signature::<SpendAuth>::from([0xff; 64])),
+        Err(Error::InvalidSignature)
+    ));
+}
+
+#[test]
+fn batch_verifier_container_api() {
+    let mut rng = thread_rng();
+    let msg = b"BatchVerifyTest";
+    let spendauth = SigningKey::<SpendAuth>::new(&mut rng);
+    let binding = SigningKey::<Binding>::new(&mut rng);
+    let spendauth_items: Vec<batch::Item> = (0..4)
+        .map(|_| {
+            let sig = spendauth.sign(&mut rng, &msg[..]);
+            (VerificationKey::from(&spendauth).into(), sig, msg).into()
+        })
+        .collect();
+    let binding_items: Vec<batch::Item> = (0..4)
+        .map(|_| {
+            let sig = binding.sign(&mut rng, &msg[..]);
+            (VerificationKey::from(&binding).into(), sig, msg).into()
+        })
+        .collect();
+
+    let mut block: batch::Verifier = spendauth_items.iter().cloned().collect();
+    let mut tx = batch::Verifier::with_capacity(4);
+    assert!(tx.is_empty());
+    assert!(tx.capacity() >= 4);
+    tx.extend(binding_items.iter().cloned());
+    assert_eq!(tx.len(), 4);
+
+    block.merge(tx);
+    assert_eq!(block.len(), 8);
+    assert_eq!(
+        block.items().iter().filter(|i| i.is_spend_auth()).count(),
+        4
+    );
+    assert_eq!(block.items().iter().filter(|i| i.is_binding()).count(), 4);
+    assert_eq!(
+        block.items()[0].verification_key_bytes(),
+        VerificationKey::from(&spendauth).to_bytes()
+    );
+    assert_eq!(
+        block.items()[4].verification_key_bytes(),
+        VerificationKey::from(&binding).to_bytes()
+    );
+
+    let capacity = block.capacity();
+    assert!(block.verify_and_clear(&mut rng).is_ok());
+    assert!(block.is_empty());
+    assert_eq!(block.capacity(), capacity);
+
+    let bad_sig = spendauth.sign(&mut rng, b"bad");
+    block.queue((VerificationKey::from(&spendauth).into(), bad_sig, msg));
+    assert_eq!(block.items()[0].signature_bytes(), bad_sig.to_bytes());
+    assert!(block.verify_and_clear(&mut rng).is_err());
+    assert!(block.is_empty());
+}
+
+#[test]
+fn batch_wire_encoding() {
+    let mut rng = thread_rng();
+    let msg = b"BatchVerifyTest";
+    let mut batch = batch::Verifier::new();
+    for _ in 0..5 {
+        let sk = SigningKey::<SpendAuth>::new(&mut rng);
+        let sig = sk.sign(&mut rng, &msg[..]);
+        batch.queue((VerificationKey::from(&sk).into(), sig, msg));
+        let sk = SigningKey::<Binding>::new(&mut rng);
+        let sig = sk.sign(&mut rng, &msg[..]);
+        batch.queue((VerificationKey::from(&sk).into(), sig, msg));
+    }
+
+    // Round-trip individual items.
+    for item in batch.items() {
+        let bytes = item.to_bytes();
+        assert_eq!(bytes[0], batch::ENCODING_VERSION);
+        let decoded = batch::Item::try_from(&bytes[..]).unwrap();
+        assert_eq!(decoded.to_bytes(), bytes);
+    }
+
+    // Split the batch among workers, ship each part, and merge the results.
+    let bytes = batch.to_bytes();
+    let parts = batch::Verifier::try_from(&bytes[..]).unwrap().split(3);
+    assert_eq!(
+        parts.iter().map(|part| part.len()).collect::<Vec<_>>(),
+        [4, 3, 3]
+    );
+    let mut merged = batch::Verifier::new();
+    for part in parts {
+        let part = batch::Verifier::try_from(&part.to_bytes()[..]).unwrap();
+        merged.merge(part);
+    }
+    assert_eq!(merged.to_bytes(), bytes);
+    assert!(merged.verify(&mut rng).is_ok());
+
+    // Reject unknown versions, domains, truncated batches, and non-canonical
+    // challenges.
+    let mut item = batch.items()[0].to_bytes();
+    item[0] = 2;
+    assert_eq!(
+        batch::Item::try_from(&item[..]).unwrap_err(),
+        Error::MalformedBatchItem
+    );
+    let mut item = batch.items()[0].to_bytes();
+    item[1] = 3;
+    assert_eq!(
+        batch::Item::try_from(&item[..]).unwrap_err(),
+        Error::MalformedBatchItem
+    );
+    let mut item = batch.items()[0].to_bytes();
+    item[98..].copy_from_slice(&[0xff; 32]);
+    assert_eq!(
+        batch::Item::try_from(&item[..]).unwrap_err(),
+        Error::MalformedBatchItem
+    );
+    assert_eq!(
+        batch::Verifier::try_from(&bytes[..bytes.len() - 1]).unwrap_err(),
+        Error::MalformedBatchItem
+    );
+
+    assert_eq!(batch::Verifier::new().split(4).len(), 1);
+}
+
+#[test]
+fn decoded_items_are_not_bound_to_a_message() {
+    // The wire encoding carries the challenge instead of the message, so an
+    // item for any key can be forged by choosing s and c, and R = [s]B - [c]VK.
+    let vk = VerificationKey::from(&SigningKey::<SpendAuth>::new(thread_rng())).to_bytes();
+    let (s, c) = (Fr::from(7u64), Fr::from(11u64));
+    let point = decaf377::Encoding(vk).vartime_decompress().unwrap();
+    let r = (decaf377::Element::GENERATOR * s - point * c).vartime_compress();
+
+    let mut bytes = [0u8; batch::ITEM_LEN];
+    bytes[0] = batch::ENCODING_VERSION;
+    bytes[1] = 1;
+    bytes[2..34].copy_from_slice(&vk);
+    bytes[34..66].copy_from_slice(&r.0);
+    bytes[66..98].copy_from_slice(&s.to_bytes());
+    bytes[98..].copy_from_slice(&c.to_bytes());
+    assert!(batch::Item::try_from(&bytes[..])
+        .unwrap()
+        .verify_single()
+        .is_ok());
+}
+
+#[test]
+fn streaming_batch_verify() {
+    let mut rng = thread_rng();
+    let msg = b"BatchVerifyTest";
+    let items: Vec<batch::Item> = (0..25)
+        .map(|i| {
+            let sk = SigningKey::<SpendAuth>::new(&mut rng);
+            let sig = sk.sign(&mut rng, &msg[..]);
+            let msg: &[u8] = if i == 19 { b"bad" } else { msg };
+            (VerificationKey::from(&sk).into(), sig, &msg).into()
+        })
+        .collect();
+
+    // A valid stream, received over a channel.
+    let (tx, rx) = std::sync::mpsc::channel();
+    for (i, item) in items.iter().enumerate() {
+        if i != 19 {
+            tx.send(item.clone()).unwrap();
+        }
+    }
+    drop(tx);
+    assert!(batch::StreamingVerifier::new(8)
+        .verify_iter(&mut rng, rx)
+        .is_ok());
+
+    assert_eq!(
+        batch::StreamingVerifier::new(8).verify_iter(&mut rng, items.iter().cloned()),
+        Err(Error::ChunkVerificationFailed {
+            chunk: 2,
+            item: None
+        })
+    );
+    assert_eq!(
+        batch::StreamingVerifier::new(8)
+            .locate_failures(true)
+            .verify_iter(&mut rng, items.iter().cloned()),
+        Err(Error::ChunkVerificationFailed {
+            chunk: 2,
+            item: Some(19)
+        })
+    );
+
+    // Verification can continue past a failed chunk.
+    let mut verifier = batch::StreamingVerifier::new(8);
+    let failures = items
+        .iter()
+        .cloned()
+        .filter_map(|item| verifier.queue(&mut rng, item).err())
+        .count();
+    assert_eq!(failures, 1);
+    assert_eq!(verifier.chunks_verified(), 3);
+    assert!(verifier.finish(&mut rng).is_ok());
+}
@@ -4,6 +4,12 @@ use proptest::prelude::*;
 
 use decaf377_rdsa::*;
 
+#[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+struct FixedSignature {
+    #[serde(with = "decaf377_rdsa::serde_fixed")]
+    sig: Signature<SpendAuth>,
+}
+
 proptest! {
     #[test]
     fn secretkey_serialization(
@@ -80,4 +86,43 @@ proptest! {
         }
     }
 
+    #[test]
+    fn signature_fixed_serialization(
+        lo in prop::array::uniform32(any::<u8>()),
+        hi in prop::array::uniform32(any::<u8>()),
+    ) {
+        let mut bytes = [0u8; 64];
+        bytes[..32].copy_from_slice(&lo);
+        bytes[32..].copy_from_slice(&hi);
+        let fixed = FixedSignature { sig: bytes.into() };
+
+        // Check 1: bincode encoding should be exactly the signature bytes.
+        let bytes_bincode = bincode::serialize(&fixed).unwrap();
+        assert_eq!(&bytes[..], &bytes_bincode[..]);
+
+        // Check 2: the encoding should round trip.
+        let fixed_bincode: FixedSignature = bincode::deserialize(&bytes_bincode).unwrap();
+        assert_eq!(fixed, fixed_bincode);
+
+        // Check 3: the default encoding is unchanged, with a length prefix.
+        let bytes_legacy = bincode::serialize(&fixed.sig).unwrap();
+        assert_eq!(&64u64.to_le_bytes()[..], &bytes_legacy[..8]);
+        assert_eq!(&bytes[..], &bytes_legacy[8..]);
+    }
+}
+
+#[test]
+fn signature_fixed_accepts_legacy_json() {
+    let sig = SigningKey::<SpendAuth>::new(rand::thread_rng()).sign_deterministic(b"legacy");
+
+    // The default encoding should be readable with the fixed encoding...
+    let legacy = format!("{{\"sig\":{}}}", serde_json::to_string(&sig).unwrap());
+    let fixed: FixedSignature = serde_json::from_str(&legacy).unwrap();
+    assert_eq!(fixed.sig, sig);
+
+    // ... and self-describing formats should encode both layouts identically.
+    assert_eq!(serde_json::to_string(&fixed).unwrap(), legacy);
+
+    // Sequences of the wrong length are rejected.
+    assert!(serde_json::from_str::<FixedSignature>(r#"{"sig":[1,2,3]}"#).is_err());
 }
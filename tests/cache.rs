@@ -0,0 +1,97 @@
+#![cfg(feature = "cache")]
+
+use std::num::NonZeroUsize;
+
+use rand::thread_rng;
+
+use decaf377_rdsa::{cache::VerificationCache, *};
+
+fn cache(capacity: usize) -> VerificationCache {
+    VerificationCache::new(NonZeroUsize::new(capacity).unwrap())
+}
+
+#[test]
+fn single_verification_is_cached() {
+    let cache = cache(16);
+    let sk = SigningKey::<SpendAuth>::new(thread_rng());
+    let vk = VerificationKey::from(&sk);
+    let sig = sk.sign(thread_rng(), b"Cached");
+
+    assert!(vk.verify_cached(b"Cached", &sig, &cache).is_ok());
+    assert_eq!(cache.len(), 1);
+    assert!(vk.verify_cached(b"Cached", &sig, &cache).is_ok());
+    assert_eq!(cache.len(), 1);
+
+    // A cached signature is not accepted for a different message.
+    assert!(vk.verify_cached(b"Other", &sig, &cache).is_err());
+    assert_eq!(cache.len(), 1);
+}
+
+#[test]
+fn failures_are_not_cached() {
+    let cache = cache(16);
+    let sk = SigningKey::<Binding>::new(thread_rng());
+    let vk = VerificationKey::from(&sk);
+    let sig = sk.sign(thread_rng(), b"bad");
+
+    for _ in 0..2 {
+        assert!(vk.verify_cached(b"Cached", &sig, &cache).is_err());
+        assert!(cache.is_empty());
+    }
+}
+
+#[test]
+fn batch_verification_is_cached() {
+    let mut rng = thread_rng();
+    let cache = cache(64);
+    let mut items = Vec::new();
+    for _ in 0..16 {
+        let sk = SigningKey::<SpendAuth>::new(&mut rng);
+        let vk = VerificationKey::from(&sk);
+        let sig = sk.sign(&mut rng, b"Cached");
+        items.push(batch::Item::from((vk.into(), sig, b"Cached")));
+    }
+
+    // Single verification and batch verification share entries.
+    assert!(items[0].clone().verify_single_cached(&cache).is_ok());
+    assert_eq!(cache.len(), 1);
+
+    let mut batch = batch::Verifier::new();
+    for item in items.iter() {
+        batch.queue(item.clone());
+    }
+    assert!(batch.verify_cached(&mut rng, &cache).is_ok());
+    assert_eq!(cache.len(), 16);
+
+    // A batch with an invalid item fails even when the valid items are cached,
+    // and the invalid item is not recorded.
+    let sk = SigningKey::<SpendAuth>::new(&mut rng);
+    let bad = batch::Item::from((
+        VerificationKey::from(&sk).into(),
+        sk.sign(&mut rng, b"bad"),
+        b"Cached",
+    ));
+    let mut batch = batch::Verifier::new();
+    for item in items.iter().chain(Some(&bad)) {
+        batch.queue(item.clone());
+    }
+    assert!(batch.verify_cached(&mut rng, &cache).is_err());
+    assert!(bad.verify_single_cached(&cache).is_err());
+    assert_eq!(cache.len(), 16);
+}
+
+#[test]
+fn cache_is_bounded() {
+    let cache = cache(4);
+    let sk = SigningKey::<SpendAuth>::new(thread_rng());
+    let vk = VerificationKey::from(&sk);
+    for i in 0..8u8 {
+        let sig = sk.sign(thread_rng(), &[i]);
+        assert!(vk.verify_cached(&[i], &sig, &cache).is_ok());
+    }
+    assert_eq!(cache.len(), 4);
+    assert_eq!(cache.capacity().get(), 4);
+
+    cache.clear();
+    assert!(cache.is_empty());
+}
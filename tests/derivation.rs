@@ -0,0 +1,84 @@
+use decaf377_rdsa::{derivation::*, *};
+
+fn seed() -> Vec<u8> {
+    (0u8..64).collect()
+}
+
+#[test]
+fn seed_derivation_vectors() {
+    let sk = SigningKey::<SpendAuth>::from_seed(&seed()).unwrap();
+    assert_eq!(
+        hex::encode(sk.to_bytes()),
+        "ef01cefd35959e9eacffd68f09adfcc5f6a7f1ccebc0e643ef74952e50b10804"
+    );
+    let sk = SigningKey::<Binding>::from_seed(&seed()).unwrap();
+    assert_eq!(
+        hex::encode(sk.to_bytes()),
+        "2afa16bc77166a2f645310cc87530e7eff96741194fc0a7e72aae6f2ef925c01"
+    );
+}
+
+#[test]
+fn short_seeds_are_rejected() {
+    assert!(matches!(
+        SigningKey::<SpendAuth>::from_seed(&[0u8; MIN_SEED_LEN - 1]),
+        Err(Error::InvalidSeed)
+    ));
+    assert!(ExtendedSigningKey::from_seed(&[0u8; MIN_SEED_LEN - 1]).is_err());
+}
+
+#[test]
+fn hierarchical_derivation_vectors() {
+    let master = ExtendedSigningKey::from_seed(&seed()).unwrap();
+    assert_eq!(
+        hex::encode(master.signing_key().to_bytes()),
+        "ef01cefd35959e9eacffd68f09adfcc5f6a7f1ccebc0e643ef74952e50b10804"
+    );
+    assert_eq!(
+        hex::encode(master.chain_code()),
+        "031181f3ee7766c0f18aa954c5bf56230678f035816b7d0ca94f512b99bfd01c"
+    );
+
+    let child = master.derive_path(&[HARDENED + 44, 1, 7]);
+    assert_eq!(
+        hex::encode(child.signing_key().to_bytes()),
+        "f3cce1c1e669cfd6d7c4d27df0d94f54c9a8d810ba266fce799a51071fa37f02"
+    );
+    assert_eq!(
+        hex::encode(child.chain_code()),
+        "942fb7d4d010891a16ceda47c78c6f8cea6204b69681c4d4fd757c9eb397182c"
+    );
+    assert_eq!(
+        hex::encode(VerificationKey::from(&child.signing_key()).to_bytes()),
+        "064f167e55f2884637e1f3e06f960985ea9b61b89774cea7a7cd39ca0c1e460c"
+    );
+}
+
+#[test]
+fn public_derivation_matches_private_derivation() {
+    let account = ExtendedSigningKey::from_seed(&seed())
+        .unwrap()
+        .derive_child(HARDENED);
+
+    let private = account.derive_path(&[0, 5]);
+    let public = account
+        .to_extended_verification_key()
+        .derive_path(&[0, 5])
+        .unwrap();
+    assert!(public == private.to_extended_verification_key());
+
+    let msg = b"DerivationTest";
+    let sig = private.signing_key().sign_deterministic(msg);
+    assert!(public.verification_key().verify(msg, &sig).is_ok());
+}
+
+#[test]
+fn hardened_public_derivation_is_rejected() {
+    let xvk = ExtendedSigningKey::from_seed(&seed())
+        .unwrap()
+        .to_extended_verification_key();
+    assert!(matches!(
+        xvk.derive_child(HARDENED),
+        Err(Error::HardenedDerivation)
+    ));
+}
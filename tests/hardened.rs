@@ -0,0 +1,40 @@
+use rand::thread_rng;
+
+use decaf377_rdsa::*;
+
+#[test]
+fn hardened_signatures_verify() {
+    let mut rng = thread_rng();
+    let msg = b"HardenedSigningTest";
+
+    let sk = SigningKey::<SpendAuth>::new(&mut rng);
+    let sig = sk.sign_hardened(&mut rng, msg).unwrap();
+    assert!(VerificationKey::from(&sk).verify(msg, &sig).is_ok());
+
+    let sk = SigningKey::<Binding>::new(&mut rng);
+    let sig = sk.sign_hardened(&mut rng, msg).unwrap();
+    assert!(VerificationKey::from(&sk).verify(msg, &sig).is_ok());
+}
+
+#[test]
+fn hardened_deterministic_signatures_match() {
+    let sk = SigningKey::<SpendAuth>::new(thread_rng());
+    let msg = b"HardenedSigningTest";
+
+    assert_eq!(
+        sk.sign_deterministic_hardened(msg).unwrap().to_bytes(),
+        sk.sign_deterministic(msg).to_bytes()
+    );
+}
+
+#[test]
+fn hardened_signing_of_randomized_keys() {
+    let mut rng = thread_rng();
+    let sk = SigningKey::<SpendAuth>::new(&mut rng);
+    let rsk = sk.randomize(&Fr::from(7u64));
+    let msg = b"HardenedSigningTest";
+
+    let sig = rsk.sign_hardened(&mut rng, msg).unwrap();
+    let rvk = VerificationKey::from(&sk).randomize(&Fr::from(7u64));
+    assert!(rvk.verify(msg, &sig).is_ok());
+}
@@ -0,0 +1,69 @@
+#![cfg(feature = "key_cache")]
+
+use rand::thread_rng;
+
+use decaf377_rdsa::{key_cache, *};
+
+// The decompression cache is global, so all checks are in one test.
+#[test]
+fn verification_keys_are_cached() {
+    let mut rng = thread_rng();
+    assert_eq!(key_cache::capacity(), key_cache::DEFAULT_CAPACITY);
+
+    // Keys derived from signing keys bypass the cache, as do randomized keys.
+    let sks: Vec<_> = (0..64)
+        .map(|_| SigningKey::<SpendAuth>::new(&mut rng))
+        .collect();
+    let vk_bytes: Vec<VerificationKeyBytes<SpendAuth>> = sks
+        .iter()
+        .map(|sk| VerificationKey::from(sk).into())
+        .collect();
+    let vk = VerificationKey::from(&sks[0]);
+    vk.randomize(&Fr::from(7u64));
+    assert_eq!(key_cache::len(), 0);
+
+    let vk = VerificationKey::try_from(vk_bytes[0]).unwrap();
+    assert_eq!(key_cache::len(), 1);
+    assert_eq!(VerificationKey::try_from(vk_bytes[0]).unwrap(), vk);
+    assert_eq!(key_cache::len(), 1);
+
+    // The same bytes in another domain are a separate entry.
+    let binding = VerificationKey::<Binding>::try_from(<[u8; 32]>::from(vk_bytes[0])).unwrap();
+    assert_eq!(binding.to_bytes(), vk.to_bytes());
+    assert_eq!(key_cache::len(), 2);
+
+    // Invalid keys are not cached.
+    assert!(VerificationKey::<SpendAuth>::try_from([0xff; 32]).is_err());
+    assert_eq!(key_cache::len(), 2);
+
+    // Batch verification and single verification populate the cache.
+    let msg = b"Cached";
+    let mut batch = batch::Verifier::new();
+    for (sk, vk_bytes) in sks.iter().zip(&vk_bytes) {
+        batch.queue((*vk_bytes, sk.sign(&mut rng, msg), msg));
+    }
+    let item = batch.items()[5].clone();
+    assert!(batch.verify(&mut rng).is_ok());
+    assert_eq!(key_cache::len(), 65);
+    assert!(item.verify_single().is_ok());
+
+    let vks = VerificationKey::batch_try_from(&vk_bytes).unwrap();
+    assert_eq!(vks.len(), 64);
+    assert_eq!(key_cache::len(), 65);
+
+    // The cache is bounded, and shrinking it evicts keys.
+    key_cache::set_capacity(20);
+    assert_eq!(key_cache::capacity(), 20);
+    assert!(key_cache::len() <= 20);
+    for vk_bytes in &vk_bytes {
+        assert!(VerificationKey::try_from(*vk_bytes).is_ok());
+    }
+    assert!(key_cache::len() <= 20);
+    key_cache::clear();
+    assert_eq!(key_cache::len(), 0);
+
+    key_cache::set_capacity(0);
+    assert!(VerificationKey::try_from(vk_bytes[0]).is_ok());
+    assert_eq!(key_cache::len(), 0);
+    key_cache::set_capacity(key_cache::DEFAULT_CAPACITY);
+}
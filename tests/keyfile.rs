@@ -0,0 +1,114 @@
+#![cfg(feature = "encryption")]
+
+use rand::thread_rng;
+use rand_chacha::ChaChaRng;
+use rand_core::SeedableRng;
+
+use decaf377_rdsa::{keyfile::KdfParams, *};
+
+const PASSWORD: &[u8] = b"correct horse battery staple";
+
+/// The key `[1; 32]`, encrypted under `PASSWORD` with `KdfParams::new(64, 1, 1)`
+/// and salt and nonce drawn from `ChaChaRng::seed_from_u64(0)`.
+const VECTOR: &str = "64333737726473610101f87b3b3a8641ee29d03281c155db3bb45fa08149b174e646dd0c643c2047150b400000000100000001000000b2f7f581d6de3c06a822fd6e7e8265fbc00f8401696a5bdc34f5a6d2ff3f922f58a28c18576b71e51b9703baec771252abea3a072c5cd33d8d8adf31dd27c9e98b4d4440aa27f5c19054667d032cee65ea4eb172737e80f9";
+
+fn vector_key() -> SigningKey<SpendAuth> {
+    SigningKey::try_from([1u8; 32]).unwrap()
+}
+
+#[test]
+fn encryption_matches_vector() {
+    let params = KdfParams::new(64, 1, 1).unwrap();
+    let file = vector_key().to_encrypted_with_params(ChaChaRng::seed_from_u64(0), PASSWORD, params);
+    assert_eq!(hex::encode(file), VECTOR);
+}
+
+#[test]
+fn decryption_matches_vector() {
+    let file = hex::decode(VECTOR).unwrap();
+    let sk = SigningKey::<SpendAuth>::from_encrypted(&file, PASSWORD).unwrap();
+    assert_eq!(sk.to_bytes(), vector_key().to_bytes());
+}
+
+#[test]
+fn encryption_roundtrip_default_params() {
+    let sk = SigningKey::<Binding>::new(thread_rng());
+    let file = sk.to_encrypted(thread_rng(), PASSWORD);
+    let sk_decrypted = SigningKey::<Binding>::from_encrypted(&file, PASSWORD).unwrap();
+    assert_eq!(sk.to_bytes(), sk_decrypted.to_bytes());
+}
+
+#[test]
+fn decryption_failures() {
+    let file = hex::decode(VECTOR).unwrap();
+
+    assert_eq!(
+        SigningKey::<SpendAuth>::from_encrypted(&file, b"wrong password").unwrap_err(),
+        Error::DecryptionFailed
+    );
+    assert_eq!(
+        SigningKey::<Binding>::from_encrypted(&file, PASSWORD).unwrap_err(),
+        Error::WrongDomain
+    );
+
+    // The header is authenticated, so tampering with the verification key
+    // is detected even though it is stored in the clear.
+    let mut tampered = file.clone();
+    tampered[10] ^= 1;
+    assert_eq!(
+        SigningKey::<SpendAuth>::from_encrypted(&tampered, PASSWORD).unwrap_err(),
+        Error::DecryptionFailed
+    );
+
+    let mut bad_magic = file.clone();
+    bad_magic[0] ^= 1;
+    assert_eq!(
+        SigningKey::<SpendAuth>::from_encrypted(&bad_magic, PASSWORD).unwrap_err(),
+        Error::MalformedKeyFile
+    );
+
+    assert!(SigningKey::<SpendAuth>::from_encrypted(&file[1..], PASSWORD).is_err());
+}
+
+#[test]
+fn kdf_params_are_validated() {
+    assert_eq!(
+        KdfParams::new(0, 1, 1).unwrap_err(),
+        Error::InvalidKdfParams
+    );
+    assert_eq!(
+        KdfParams::new(64, 0, 1).unwrap_err(),
+        Error::InvalidKdfParams
+    );
+}
+
+#[test]
+fn kdf_params_are_bounded_on_decryption() {
+    let file = hex::decode(VECTOR).unwrap();
+
+    // A crafted header demanding 4 TiB of memory is rejected before the key
+    // is derived.
+    let mut crafted = file.clone();
+    crafted[42..46].copy_from_slice(&u32::MAX.to_le_bytes());
+    assert_eq!(
+        SigningKey::<SpendAuth>::from_encrypted(&crafted, PASSWORD).unwrap_err(),
+        Error::InvalidKdfParams
+    );
+
+    let mut crafted = file.clone();
+    crafted[46..50].copy_from_slice(&u32::MAX.to_le_bytes());
+    assert_eq!(
+        SigningKey::<SpendAuth>::from_encrypted(&crafted, PASSWORD).unwrap_err(),
+        Error::InvalidKdfParams
+    );
+
+    // Callers can choose tighter or looser limits.
+    let tight = KdfParams::new(32, 1, 1).unwrap();
+    assert_eq!(
+        SigningKey::<SpendAuth>::from_encrypted_with_limits(&file, PASSWORD, tight).unwrap_err(),
+        Error::InvalidKdfParams
+    );
+    let exact = KdfParams::new(64, 1, 1).unwrap();
+    let sk = SigningKey::<SpendAuth>::from_encrypted_with_limits(&file, PASSWORD, exact).unwrap();
+    assert_eq!(sk.to_bytes(), vector_key().to_bytes());
+}
@@ -0,0 +1,65 @@
+#![cfg(feature = "merlin")]
+
+use rand::thread_rng;
+
+use decaf377_rdsa::{merlin::Transcript, *};
+
+fn transcript(msg: &[u8]) -> Transcript {
+    let mut transcript = Transcript::new(b"decaf377-rdsa test protocol");
+    transcript.append_message(b"msg", msg);
+    transcript
+}
+
+#[test]
+fn transcript_signatures_verify() {
+    let mut rng = thread_rng();
+
+    let sk = SigningKey::<SpendAuth>::new(&mut rng);
+    let sig = sk.sign_with_transcript(&mut rng, &mut transcript(b"hello"));
+    assert!(VerificationKey::from(&sk)
+        .verify_with_transcript(&mut transcript(b"hello"), &sig)
+        .is_ok());
+
+    let sk = SigningKey::<Binding>::new(&mut rng);
+    let sig = sk.sign_with_transcript(&mut rng, &mut transcript(b"hello"));
+    assert!(VerificationKey::from(&sk)
+        .verify_with_transcript(&mut transcript(b"hello"), &sig)
+        .is_ok());
+}
+
+#[test]
+fn transcript_signatures_are_bound_to_the_transcript() {
+    let mut rng = thread_rng();
+    let sk = SigningKey::<SpendAuth>::new(&mut rng);
+    let vk = VerificationKey::from(&sk);
+    let sig = sk.sign_with_transcript(&mut rng, &mut transcript(b"hello"));
+
+    assert_eq!(
+        vk.verify_with_transcript(&mut transcript(b"goodbye"), &sig),
+        Err(Error::InvalidSignature)
+    );
+    assert_eq!(
+        vk.verify_with_transcript(&mut Transcript::new(b"another protocol"), &sig),
+        Err(Error::InvalidSignature)
+    );
+    assert_eq!(vk.verify(b"hello", &sig), Err(Error::InvalidSignature));
+}
+
+#[test]
+fn transcripts_agree_after_signing() {
+    let mut rng = thread_rng();
+    let sk = SigningKey::<SpendAuth>::new(&mut rng);
+
+    let mut prover = transcript(b"hello");
+    let mut verifier = transcript(b"hello");
+    let sig = sk.sign_with_transcript(&mut rng, &mut prover);
+    VerificationKey::from(&sk)
+        .verify_with_transcript(&mut verifier, &sig)
+        .unwrap();
+
+    let mut a = [0u8; 32];
+    let mut b = [0u8; 32];
+    prover.challenge_bytes(b"next", &mut a);
+    verifier.challenge_bytes(b"next", &mut b);
+    assert_eq!(a, b);
+}
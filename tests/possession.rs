@@ -0,0 +1,92 @@
+use rand::thread_rng;
+
+use decaf377_rdsa::*;
+
+#[test]
+fn proofs_of_possession_verify() {
+    let mut rng = thread_rng();
+
+    let sk = SigningKey::<SpendAuth>::new(&mut rng);
+    let proof = sk.prove_possession(&mut rng);
+    assert!(VerificationKey::from(&sk).verify_possession(&proof).is_ok());
+
+    let sk = SigningKey::<Binding>::new(&mut rng);
+    let proof = sk.prove_possession(&mut rng);
+    assert!(VerificationKey::from(&sk).verify_possession(&proof).is_ok());
+}
+
+#[test]
+fn proof_for_other_key_is_rejected() {
+    let mut rng = thread_rng();
+    let sk1 = SigningKey::<SpendAuth>::new(&mut rng);
+    let sk2 = SigningKey::<SpendAuth>::new(&mut rng);
+
+    let proof = sk1.prove_possession(&mut rng);
+    assert_eq!(
+        VerificationKey::from(&sk2).verify_possession(&proof),
+        Err(Error::InvalidSignature)
+    );
+}
+
+#[test]
+fn proofs_and_signatures_are_not_interchangeable() {
+    let mut rng = thread_rng();
+    let sk = SigningKey::<SpendAuth>::new(&mut rng);
+    let vk = VerificationKey::from(&sk);
+    let vk_bytes = vk.to_bytes();
+
+    // A signature on the key bytes is not a proof of possession...
+    let sig = sk.sign(&mut rng, &vk_bytes);
+    let proof = ProofOfPossession::from(sig.to_bytes());
+    assert_eq!(vk.verify_possession(&proof), Err(Error::InvalidSignature));
+
+    // ...and a proof of possession is not a signature on the key bytes.
+    let proof = sk.prove_possession(&mut rng);
+    let sig = Signature::from(proof.to_bytes());
+    assert_eq!(vk.verify(&vk_bytes, &sig), Err(Error::InvalidSignature));
+}
+
+#[test]
+fn batch_verify_proofs_of_possession() {
+    let mut rng = thread_rng();
+    let mut batch = batch::Verifier::new();
+    for _ in 0..8 {
+        let sk = SigningKey::<SpendAuth>::new(&mut rng);
+        batch.queue((
+            VerificationKey::from(&sk).into(),
+            sk.prove_possession(&mut rng),
+        ));
+        let sk = SigningKey::<Binding>::new(&mut rng);
+        batch.queue((
+            VerificationKey::from(&sk).into(),
+            sk.prove_possession(&mut rng),
+        ));
+    }
+    // Proofs can be mixed with ordinary signatures.
+    let sk = SigningKey::<SpendAuth>::new(&mut rng);
+    let msg = b"PossessionTest";
+    batch.queue((
+        VerificationKey::from(&sk).into(),
+        sk.sign(&mut rng, msg),
+        msg,
+    ));
+    assert!(batch.verify(rng).is_ok());
+}
+
+#[test]
+fn batch_rejects_invalid_proof_of_possession() {
+    let mut rng = thread_rng();
+    let sk1 = SigningKey::<SpendAuth>::new(&mut rng);
+    let sk2 = SigningKey::<SpendAuth>::new(&mut rng);
+
+    let mut batch = batch::Verifier::new();
+    batch.queue((
+        VerificationKey::from(&sk1).into(),
+        sk1.prove_possession(&mut rng),
+    ));
+    batch.queue((
+        VerificationKey::from(&sk2).into(),
+        sk1.prove_possession(&mut rng),
+    ));
+    assert!(batch.verify(rng).is_err());
+}
@@ -0,0 +1,91 @@
+use rand::{thread_rng, CryptoRng, RngCore};
+
+use decaf377_rdsa::*;
+
+#[test]
+fn presigned_signatures_verify() {
+    let mut rng = thread_rng();
+    let sk = SigningKey::<SpendAuth>::new(&mut rng);
+    let vk = VerificationKey::from(&sk);
+
+    let pool = sk.presign_many(&mut rng, 8);
+    for (i, presig) in pool.into_iter().enumerate() {
+        assert_eq!(presig.verification_key_bytes(), vk.into());
+        let msg = format!("PresignatureTest {}", i);
+        let sig = sk.sign_with_presignature(presig, msg.as_bytes()).unwrap();
+        assert!(vk.verify(msg.as_bytes(), &sig).is_ok());
+    }
+}
+
+#[test]
+fn presignatures_use_distinct_nonces() {
+    let mut rng = thread_rng();
+    let sk = SigningKey::<Binding>::new(&mut rng);
+    let msg = b"PresignatureTest";
+
+    let sig1 = sk
+        .sign_with_presignature(sk.presign(&mut rng), msg)
+        .unwrap();
+    let sig2 = sk
+        .sign_with_presignature(sk.presign(&mut rng), msg)
+        .unwrap();
+    assert_ne!(sig1.to_bytes()[..32], sig2.to_bytes()[..32]);
+}
+
+#[test]
+fn presignature_for_other_key_is_rejected() {
+    let mut rng = thread_rng();
+    let sk1 = SigningKey::<SpendAuth>::new(&mut rng);
+    let sk2 = SigningKey::<SpendAuth>::new(&mut rng);
+
+    let presig = sk1.presign(&mut rng);
+    assert_eq!(
+        sk2.sign_with_presignature(presig, b"PresignatureTest"),
+        Err(Error::WrongPresignature)
+    );
+}
+
+/// An RNG that has failed, and only returns zeros.
+struct FailedRng;
+
+impl RngCore for FailedRng {
+    fn next_u32(&mut self) -> u32 {
+        0
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        0
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        dest.fill(0);
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        dest.fill(0);
+        Ok(())
+    }
+}
+
+impl CryptoRng for FailedRng {}
+
+#[test]
+fn presignature_nonces_are_separate_from_signing_nonces() {
+    let sk = SigningKey::<SpendAuth>::new(thread_rng());
+    let r_bytes = |sig: Signature<SpendAuth>| <[u8; 64]>::from(sig)[..32].to_vec();
+
+    let signed = r_bytes(sk.sign_deterministic(b""));
+    let presigned = r_bytes(
+        sk.sign_with_presignature(sk.presign(FailedRng), b"")
+            .unwrap(),
+    );
+    assert_ne!(signed, presigned);
+
+    let pool = sk.presign_many(FailedRng, 2);
+    let mut r_values = pool
+        .into_iter()
+        .map(|presig| r_bytes(sk.sign_with_presignature(presig, b"").unwrap()))
+        .collect::<Vec<_>>();
+    r_values.dedup();
+    assert_eq!(r_values.len(), 2);
+}
@@ -72,7 +72,7 @@ impl<D: Domain> SignatureCase<D> {
             }
             Tweak::ChangePubkey => {
                 // Changing the public key makes the signature invalid.
-                let mut bytes: [u8; 32] = self.pk_bytes.clone().into();
+                let mut bytes: [u8; 32] = self.pk_bytes.into();
                 let j = (bytes[2] & 31) as usize;
                 bytes[2] ^= 0x23;
                 bytes[2] |= 0x99;
@@ -0,0 +1,86 @@
+use rand::thread_rng;
+
+use decaf377_rdsa::{randomization::*, *};
+
+fn setup(n: usize) -> (Vec<SigningKey<SpendAuth>>, Vec<Fr>, Vec<KeyCommitment>) {
+    let mut rng = thread_rng();
+    let sks: Vec<_> = (0..n).map(|_| SigningKey::new(&mut rng)).collect();
+    let blindings: Vec<_> = (0..n).map(|i| Fr::from(1000 + i as u64)).collect();
+    let commitments = sks
+        .iter()
+        .zip(&blindings)
+        .map(|(sk, v)| KeyCommitment::new(&sk.into(), v))
+        .collect();
+    (sks, blindings, commitments)
+}
+
+#[test]
+fn proof_verifies_for_each_index() {
+    let mut rng = thread_rng();
+    let (sks, blindings, commitments) = setup(4);
+
+    for index in 0..4 {
+        let randomizer = Randomizer::new(&mut rng);
+        let (_, rk) = randomizer.randomize_key_pair(&sks[index]);
+        let proof = RandomizationProof::prove(
+            &mut rng,
+            &rk,
+            &commitments,
+            index,
+            &blindings[index],
+            &randomizer,
+        )
+        .unwrap();
+        assert!(proof.verify(&rk, &commitments).is_ok());
+    }
+}
+
+#[test]
+fn proof_for_unrelated_key_is_rejected() {
+    let mut rng = thread_rng();
+    let (sks, blindings, commitments) = setup(3);
+    let randomizer = Randomizer::new(&mut rng);
+    let (_, rk) = randomizer.randomize_key_pair(&sks[1]);
+
+    // The prover refuses to prove a false statement.
+    assert_eq!(
+        RandomizationProof::prove(&mut rng, &rk, &commitments, 0, &blindings[0], &randomizer),
+        Err(Error::InvalidProof)
+    );
+
+    // A valid proof does not verify for another randomized key, or against
+    // another set of commitments.
+    let proof =
+        RandomizationProof::prove(&mut rng, &rk, &commitments, 1, &blindings[1], &randomizer)
+            .unwrap();
+    let other = VerificationKey::from(&SigningKey::<SpendAuth>::new(&mut rng));
+    assert_eq!(proof.verify(&other, &commitments), Err(Error::InvalidProof));
+    assert_eq!(
+        proof.verify(&rk, &[commitments[1], commitments[0], commitments[2]]),
+        Err(Error::InvalidProof)
+    );
+}
+
+#[test]
+fn encoding_round_trip() {
+    let mut rng = thread_rng();
+    let (sks, blindings, commitments) = setup(2);
+    let randomizer = Randomizer::new(&mut rng);
+    let (_, rk) = randomizer.randomize_key_pair(&sks[0]);
+    let proof =
+        RandomizationProof::prove(&mut rng, &rk, &commitments, 0, &blindings[0], &randomizer)
+            .unwrap();
+
+    let bytes = proof.to_bytes();
+    assert_eq!(bytes.len(), 4 + 2 * 96);
+    let decoded = RandomizationProof::try_from(&bytes[..]).unwrap();
+    assert!(decoded == proof);
+
+    let commitments: Vec<KeyCommitment> = commitments
+        .iter()
+        .map(|c| KeyCommitment::try_from(c.to_bytes()).unwrap())
+        .collect();
+    assert!(decoded.verify(&rk, &commitments).is_ok());
+
+    assert!(RandomizationProof::try_from(&bytes[..bytes.len() - 1]).is_err());
+}
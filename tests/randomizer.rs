@@ -0,0 +1,74 @@
+use rand::thread_rng;
+
+use decaf377_rdsa::*;
+
+#[test]
+fn randomized_key_pairs_match() {
+    let mut rng = thread_rng();
+    let sk = SigningKey::<SpendAuth>::new(&mut rng);
+    let vk = VerificationKey::from(&sk);
+    let randomizer = Randomizer::new(&mut rng);
+
+    let (rsk, rvk) = randomizer.randomize_key_pair(&sk);
+    assert_eq!(randomizer.randomize_verification_key(&vk), rvk);
+    assert_eq!(
+        VerificationKey::from(&randomizer.randomize_signing_key(&sk)),
+        rvk
+    );
+    assert_eq!(vk.randomize(&randomizer.to_scalar()), rvk);
+
+    let msg = b"RandomizerTest";
+    let sig = rsk.sign(&mut rng, msg);
+    assert!(rvk.verify(msg, &sig).is_ok());
+}
+
+#[test]
+fn derivation_is_deterministic_and_context_bound() {
+    let a = Randomizer::derive(b"seed", b"context");
+    assert_eq!(a, Randomizer::derive(b"seed", b"context"));
+    assert_ne!(a, Randomizer::derive(b"seed", b"other context"));
+    assert_ne!(a, Randomizer::derive(b"seedc", b"ontext"));
+}
+
+#[test]
+fn byte_encoding_round_trip() {
+    let randomizer = Randomizer::new(thread_rng());
+    let bytes = randomizer.to_bytes();
+    assert_eq!(Randomizer::try_from(bytes).unwrap(), randomizer);
+    assert_eq!(Randomizer::try_from(&bytes[..]).unwrap(), randomizer);
+
+    assert_eq!(
+        Randomizer::try_from([0xff; 32]),
+        Err(Error::MalformedRandomizer)
+    );
+    assert_eq!(
+        Randomizer::try_from(&bytes[..31]),
+        Err(Error::WrongSliceLength {
+            expected: 32,
+            found: 31
+        })
+    );
+}
+
+#[test]
+fn serde_round_trip() {
+    let randomizer = Randomizer::new(thread_rng());
+    let json = serde_json::to_string(&randomizer).unwrap();
+    assert_eq!(
+        serde_json::from_str::<Randomizer>(&json).unwrap(),
+        randomizer
+    );
+
+    let bytes = bincode::serialize(&randomizer).unwrap();
+    assert_eq!(bytes, randomizer.to_bytes());
+    assert_eq!(
+        bincode::deserialize::<Randomizer>(&bytes).unwrap(),
+        randomizer
+    );
+}
+
+#[test]
+fn debug_output_is_redacted() {
+    let randomizer = Randomizer::derive(b"seed", b"context");
+    assert_eq!(format!("{:?}", randomizer), "Randomizer(<redacted>)");
+}
@@ -0,0 +1,75 @@
+#![cfg(feature = "service")]
+
+use std::time::Duration;
+
+use futures::future::join_all;
+use rand::thread_rng;
+use tower::{Service as _, ServiceExt};
+
+use decaf377_rdsa::{batch::service::Service, *};
+
+fn spendauth_item(valid: bool) -> batch::Item {
+    let mut rng = thread_rng();
+    let sk = SigningKey::<SpendAuth>::new(&mut rng);
+    let vk = VerificationKey::from(&sk);
+    let msg = b"ServiceTest";
+    let sig = if valid {
+        sk.sign(&mut rng, &msg[..])
+    } else {
+        sk.sign(&mut rng, b"bad")
+    };
+    (vk.into(), sig, msg).into()
+}
+
+#[tokio::test]
+async fn service_flushes_full_batches() {
+    // The latency is long enough that only the batch size triggers a flush.
+    let service = Service::new(8, Duration::from_secs(3600));
+    let results = join_all((0..32).map(|_| service.verify(spendauth_item(true)))).await;
+    assert!(results.iter().all(Result::is_ok));
+}
+
+#[tokio::test]
+async fn service_flushes_after_latency() {
+    let service = Service::new(1024, Duration::from_millis(10));
+    let results = join_all((0..3).map(|_| service.verify(spendauth_item(true)))).await;
+    assert!(results.iter().all(Result::is_ok));
+}
+
+#[tokio::test]
+async fn service_attributes_failures() {
+    let bad_index = 5;
+    let service = Service::new(16, Duration::from_millis(10));
+    let results = join_all((0..16).map(|i| service.verify(spendauth_item(i != bad_index)))).await;
+    for (i, result) in results.into_iter().enumerate() {
+        if i != bad_index {
+            assert!(result.is_ok());
+        } else {
+            assert_eq!(result, Err(Error::InvalidSignature));
+        }
+    }
+}
+
+#[tokio::test]
+async fn service_is_a_tower_service() {
+    let service = Service::new(4, Duration::from_millis(10));
+    assert!(service.clone().oneshot(spendauth_item(true)).await.is_ok());
+    assert!(service.oneshot(spendauth_item(false)).await.is_err());
+}
+
+#[tokio::test]
+async fn service_applies_backpressure() {
+    use futures::FutureExt;
+
+    let mut service = Service::with_bound(1, Duration::from_millis(10), 1);
+
+    // The worker has not run yet, so once one request is queued the service
+    // is not ready for another.
+    let first = service.ready().await.unwrap().call(spendauth_item(true));
+    assert!(service.ready().now_or_never().is_none());
+
+    // Once the worker drains the queue, the service becomes ready again.
+    assert!(first.await.is_ok());
+    let second = service.ready().await.unwrap().call(spendauth_item(true));
+    assert!(second.await.is_ok());
+}
@@ -0,0 +1,147 @@
+use rand::thread_rng;
+
+use decaf377_rdsa::{shamir::*, *};
+
+#[test]
+fn split_and_reconstruct() {
+    let mut rng = thread_rng();
+    let sk = SigningKey::<SpendAuth>::new(&mut rng);
+    let vk = VerificationKey::from(&sk);
+
+    let (shares, commitments) = sk.split(&mut rng, 3, 5).unwrap();
+    assert_eq!(shares.len(), 5);
+    assert_eq!(commitments.verification_key(), vk);
+    for share in &shares {
+        assert!(share.verify(&commitments, &vk).is_ok());
+    }
+
+    for subset in [
+        &shares[..3],
+        &shares[2..],
+        &[shares[4].clone(), shares[0].clone(), shares[2].clone()][..],
+    ] {
+        let recovered = SigningKey::reconstruct(subset, &vk).unwrap();
+        assert_eq!(recovered.to_bytes(), sk.to_bytes());
+    }
+}
+
+#[test]
+fn too_few_shares_are_rejected() {
+    let mut rng = thread_rng();
+    let sk = SigningKey::<Binding>::new(&mut rng);
+    let vk = VerificationKey::from(&sk);
+
+    let (shares, _) = sk.split(&mut rng, 3, 5).unwrap();
+    assert_eq!(
+        SigningKey::reconstruct(&shares[..2], &vk).map(|sk| sk.to_bytes()),
+        Err(Error::InvalidShare)
+    );
+    assert_eq!(
+        SigningKey::reconstruct(
+            &[shares[0].clone(), shares[0].clone(), shares[1].clone()],
+            &vk
+        )
+        .map(|sk| sk.to_bytes()),
+        Err(Error::InvalidShare)
+    );
+}
+
+#[test]
+fn shares_beyond_the_threshold_are_checked() {
+    let mut rng = thread_rng();
+    let sk = SigningKey::<SpendAuth>::new(&mut rng);
+    let vk = VerificationKey::from(&sk);
+
+    let (mut shares, _) = sk.split(&mut rng, 3, 5).unwrap();
+    assert_eq!(
+        SigningKey::reconstruct(&shares, &vk).unwrap().to_bytes(),
+        sk.to_bytes()
+    );
+
+    let mut bytes = shares[4].to_bytes();
+    bytes[3] ^= 1;
+    shares[4] = Share::try_from(&bytes[..]).unwrap();
+    assert_eq!(
+        SigningKey::reconstruct(&shares, &vk).map(|sk| sk.to_bytes()),
+        Err(Error::InvalidShare)
+    );
+}
+
+#[test]
+fn shares_are_checked_against_the_verification_key() {
+    let mut rng = thread_rng();
+    let sk = SigningKey::<SpendAuth>::new(&mut rng);
+    let other = VerificationKey::from(&SigningKey::<SpendAuth>::new(&mut rng));
+
+    let (shares, commitments) = sk.split(&mut rng, 2, 3).unwrap();
+    assert_eq!(
+        shares[0].verify(&commitments, &other),
+        Err(Error::InvalidShare)
+    );
+}
+
+#[test]
+fn invalid_thresholds_are_rejected() {
+    let sk = SigningKey::<SpendAuth>::new(thread_rng());
+    assert!(matches!(
+        sk.split(thread_rng(), 0, 3),
+        Err(Error::InvalidThreshold)
+    ));
+    assert!(matches!(
+        sk.split(thread_rng(), 4, 3),
+        Err(Error::InvalidThreshold)
+    ));
+}
+
+#[test]
+fn corrupted_share_fails_verification() {
+    let mut rng = thread_rng();
+    let sk = SigningKey::<SpendAuth>::new(&mut rng);
+    let vk = VerificationKey::from(&sk);
+
+    let (shares, commitments) = sk.split(&mut rng, 2, 3).unwrap();
+    let mut bytes = shares[1].to_bytes();
+    bytes[3] ^= 1;
+    let corrupted = Share::<SpendAuth>::try_from(&bytes[..]).unwrap();
+    assert_eq!(
+        corrupted.verify(&commitments, &vk),
+        Err(Error::InvalidShare)
+    );
+    assert_eq!(
+        SigningKey::reconstruct(&[shares[0].clone(), corrupted], &vk).map(|sk| sk.to_bytes()),
+        Err(Error::InvalidShare)
+    );
+}
+
+#[test]
+fn encoding_round_trip() {
+    let mut rng = thread_rng();
+    let sk = SigningKey::<SpendAuth>::new(&mut rng);
+    let vk = VerificationKey::from(&sk);
+
+    let (shares, commitments) = sk.split(&mut rng, 2, 3).unwrap();
+    let commitments = Commitments::<SpendAuth>::try_from(&commitments.to_bytes()[..]).unwrap();
+    let shares = shares
+        .iter()
+        .map(|share| {
+            let bytes = share.to_bytes();
+            assert_eq!(bytes.len(), SHARE_LEN);
+            Share::<SpendAuth>::try_from(&bytes[..]).unwrap()
+        })
+        .collect::<Vec<_>>();
+    for share in &shares {
+        assert!(share.verify(&commitments, &vk).is_ok());
+    }
+    assert_eq!(
+        SigningKey::reconstruct(&shares[1..], &vk)
+            .unwrap()
+            .to_bytes(),
+        sk.to_bytes()
+    );
+
+    let bytes = shares[0].to_bytes();
+    assert!(matches!(
+        Share::<Binding>::try_from(&bytes[..]),
+        Err(Error::WrongDomain)
+    ));
+}
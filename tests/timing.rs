@@ -0,0 +1,146 @@
+//! A dudect-style statistical test for timing leaks in signing and key
+//! derivation.
+//!
+//! Measurements are taken for two classes of inputs, interleaved at random: a
+//! fixed secret key with very low Hamming weight, which a variable-time scalar
+//! multiplication would process much faster than average, and uniformly random
+//! secret keys. Welch's t-test is then used to compare the two timing
+//! distributions, after discarding the slowest measurements to reduce noise
+//! from preemption. As in dudect, a |t| statistic above 10 is taken as clear
+//! evidence of a leak.
+//!
+//! The statistical tests take several seconds and are sensitive to load on the
+//! machine running them, so they are ignored by default; run them with
+//! `cargo test --release --test timing -- --ignored`.
+
+use std::time::Instant;
+
+use rand::{thread_rng, Rng};
+
+use decaf377_rdsa::*;
+
+const SAMPLES: usize = 300;
+const CROP_PERCENTILE: f64 = 0.9;
+const THRESHOLD: f64 = 10.0;
+
+/// Returns Welch's t statistic for the two classes of `(class, time)` samples.
+fn welch_t(samples: &[(bool, u128)]) -> f64 {
+    let mut times: Vec<u128> = samples.iter().map(|(_, t)| *t).collect();
+    times.sort_unstable();
+    let cutoff = times[((times.len() as f64) * CROP_PERCENTILE) as usize];
+
+    let stats = |class: bool| {
+        let xs: Vec<f64> = samples
+            .iter()
+            .filter(|(c, t)| *c == class && *t <= cutoff)
+            .map(|(_, t)| *t as f64)
+            .collect();
+        let n = xs.len() as f64;
+        let mean = xs.iter().sum::<f64>() / n;
+        let var = xs.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1.0);
+        (n, mean, var)
+    };
+
+    let (n0, m0, v0) = stats(false);
+    let (n1, m1, v1) = stats(true);
+    (m0 - m1) / (v0 / n0 + v1 / n1).sqrt()
+}
+
+fn fixed_key() -> Fr {
+    Fr::from(1u64)
+}
+
+fn random_key<R: Rng>(rng: &mut R) -> Fr {
+    let mut bytes = [0u8; 64];
+    rng.fill(&mut bytes[..]);
+    Fr::from_le_bytes_mod_order(&bytes[..])
+}
+
+#[test]
+#[ignore]
+fn key_derivation_is_constant_time() {
+    let mut rng = thread_rng();
+    let samples: Vec<(bool, u128)> = (0..2 * SAMPLES)
+        .map(|_| {
+            let class = rng.gen::<bool>();
+            let sk = if class {
+                random_key(&mut rng)
+            } else {
+                fixed_key()
+            };
+            let start = Instant::now();
+            let sk = SigningKey::<SpendAuth>::new_from_field(sk);
+            let elapsed = start.elapsed().as_nanos();
+            std::hint::black_box(sk);
+            (class, elapsed)
+        })
+        .collect();
+
+    let t = welch_t(&samples);
+    assert!(t.abs() < THRESHOLD, "timing leak detected: t = {}", t);
+}
+
+#[test]
+#[ignore]
+fn signing_is_constant_time() {
+    let mut rng = thread_rng();
+    let fixed = SigningKey::<SpendAuth>::new_from_field(fixed_key());
+    let msg = b"dudect";
+    let samples: Vec<(bool, u128)> = (0..2 * SAMPLES)
+        .map(|_| {
+            let class = rng.gen::<bool>();
+            let sk = if class {
+                SigningKey::<SpendAuth>::new_from_field(random_key(&mut rng))
+            } else {
+                fixed
+            };
+            let start = Instant::now();
+            let sig = sk.sign_deterministic(msg);
+            let elapsed = start.elapsed().as_nanos();
+            std::hint::black_box(sig);
+            (class, elapsed)
+        })
+        .collect();
+
+    let t = welch_t(&samples);
+    assert!(t.abs() < THRESHOLD, "timing leak detected: t = {}", t);
+}
+
+fn binding_basepoint() -> decaf377::Element {
+    decaf377::Element::encode_to_curve(&decaf377::Fq::from_le_bytes_mod_order(
+        blake2b_simd::blake2b(b"decaf377-rdsa-binding").as_bytes(),
+    ))
+}
+
+#[test]
+fn key_derivation_matches_variable_time_derivation() {
+    let mut rng = thread_rng();
+    for sk in [Fr::from(0u64), fixed_key(), random_key(&mut rng)] {
+        let vk: VerificationKey<SpendAuth> = (&SigningKey::new_from_field(sk)).into();
+        let expected = (decaf377::Element::GENERATOR * sk).vartime_compress().0;
+        assert_eq!(vk.to_bytes(), expected);
+
+        let vk: VerificationKey<Binding> = (&SigningKey::new_from_field(sk)).into();
+        let expected = (binding_basepoint() * sk).vartime_compress().0;
+        assert_eq!(vk.to_bytes(), expected);
+    }
+}
+
+#[test]
+fn randomization_matches_variable_time_derivation() {
+    let mut rng = thread_rng();
+    for (sk, randomizer) in [
+        (Fr::from(0u64), Fr::from(0u64)),
+        (fixed_key(), Fr::from(0u64)),
+        (random_key(&mut rng), random_key(&mut rng)),
+    ] {
+        let expected = (decaf377::Element::GENERATOR * (sk + randomizer))
+            .vartime_compress()
+            .0;
+        let sk = SigningKey::<SpendAuth>::new_from_field(sk);
+        let vk: VerificationKey<SpendAuth> = (&sk.randomize(&randomizer)).into();
+        assert_eq!(vk.to_bytes(), expected);
+        let vk: VerificationKey<SpendAuth> = (&sk).into();
+        assert_eq!(vk.randomize(&randomizer).to_bytes(), expected);
+    }
+}
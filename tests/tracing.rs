@@ -0,0 +1,113 @@
+#![cfg(feature = "tracing")]
+
+use std::{
+    io,
+    sync::{Arc, Mutex},
+};
+
+use rand::thread_rng;
+use tracing_subscriber::{filter::LevelFilter, util::SubscriberInitExt};
+
+use decaf377_rdsa::*;
+
+#[derive(Clone, Default)]
+struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+impl io::Write for Buffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Buffer {
+    fn contents(&self) -> String {
+        String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+    }
+}
+
+#[test]
+fn batch_verification_emits_events() {
+    let buffer = Buffer::default();
+    let writer = buffer.clone();
+    let _guard = tracing_subscriber::fmt()
+        .with_max_level(LevelFilter::DEBUG)
+        .with_ansi(false)
+        .with_writer(move || writer.clone())
+        .finish()
+        .set_default();
+
+    let mut rng = thread_rng();
+    let msg = b"BatchVerifyTest";
+    let mut batch = batch::Verifier::new();
+    for i in 0..4 {
+        let sk = SigningKey::<SpendAuth>::new(&mut rng);
+        let sig = sk.sign(&mut rng, &msg[..]);
+        batch.queue((VerificationKey::from(&sk).into(), sig, msg));
+        let sk = SigningKey::<Binding>::new(&mut rng);
+        let sig = sk.sign(&mut rng, &msg[..]);
+        let msg: &[u8] = if i == 2 { b"bad" } else { msg };
+        batch.queue((VerificationKey::from(&sk).into(), sig, &msg));
+    }
+    assert!(batch.verify(&mut rng).is_err());
+
+    let output = buffer.contents();
+    assert!(output.contains("batch_verify{items=8 spend_auth=4 binding=4}"));
+    assert!(output.contains("batch verification failed"));
+    assert!(output.contains("batch equation not satisfied"));
+    assert!(output.contains("monotonic_counter.batch_verifications=1"));
+    assert!(output.contains("monotonic_counter.batch_failures=1"));
+    assert!(output.contains("monotonic_counter.batch_items=8"));
+    assert!(output.contains("monotonic_counter.batch_spend_auth_items=4"));
+    assert!(output.contains("monotonic_counter.batch_binding_items=4"));
+
+    let mut batch = batch::Verifier::new();
+    batch.queue((
+        [0; 32].into(),
+        Signature::<SpendAuth>::from([0xff; 64]),
+        msg,
+    ));
+    assert!(batch.verify(&mut rng).is_err());
+    assert!(buffer.contents().contains("malformed R value"));
+}
+
+#[test]
+fn array_verification_and_fallbacks_emit_counters() {
+    let buffer = Buffer::default();
+    let writer = buffer.clone();
+    let _guard = tracing_subscriber::fmt()
+        .with_max_level(LevelFilter::DEBUG)
+        .with_ansi(false)
+        .with_writer(move || writer.clone())
+        .finish()
+        .set_default();
+
+    let mut rng = thread_rng();
+    let msg = b"BatchVerifyTest";
+    let mut batch = batch::ArrayVerifier::<4>::new();
+    let mut items = Vec::new();
+    for i in 0..3 {
+        let sk = SigningKey::<SpendAuth>::new(&mut rng);
+        let sig = sk.sign(&mut rng, &msg[..]);
+        let msg: &[u8] = if i == 1 { b"bad" } else { msg };
+        let item = batch::Item::from((VerificationKey::from(&sk).into(), sig, &msg));
+        items.push(item.clone());
+        batch.queue(item).unwrap();
+    }
+    assert!(batch.verify(&mut rng).is_err());
+
+    let output = buffer.contents();
+    assert!(output.contains("batch_verify{items=3 spend_auth=3 binding=0}"));
+    assert!(output.contains("batch equation not satisfied"));
+    assert!(output.contains("monotonic_counter.batch_failures=1"));
+    assert!(output.contains("monotonic_counter.batch_items=3"));
+
+    let verifier = batch::StreamingVerifier::new(3).locate_failures(true);
+    assert!(verifier.verify_iter(&mut rng, items).is_err());
+    let output = buffer.contents();
+    assert!(output.contains("monotonic_counter.batch_fallbacks=1"));
+    assert!(output.contains("monotonic_counter.batch_fallback_items=3"));
+}